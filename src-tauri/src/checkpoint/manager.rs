@@ -4,7 +4,9 @@ use log;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use super::{
@@ -13,6 +15,9 @@ use super::{
     FileSnapshot, FileState, FileTracker, SessionTimeline,
 };
 
+/// Quiet period after the last file write before an auto checkpoint is created
+const FILE_WRITE_DEBOUNCE: Duration = Duration::from_secs(3);
+
 /// Manages checkpoint operations for a session
 pub struct CheckpointManager {
     project_id: String,
@@ -22,6 +27,10 @@ pub struct CheckpointManager {
     pub storage: Arc<CheckpointStorage>,
     timeline: Arc<RwLock<SessionTimeline>>,
     current_messages: Arc<RwLock<Vec<String>>>, // JSONL messages
+    pending_written_files: Arc<RwLock<Vec<String>>>,
+    file_write_generation: Arc<AtomicU64>,
+    /// Assistant messages tracked since the last checkpoint of any kind
+    messages_since_checkpoint: Arc<AtomicU64>,
 }
 
 impl CheckpointManager {
@@ -57,6 +66,9 @@ impl CheckpointManager {
             storage,
             timeline: Arc::new(RwLock::new(timeline)),
             current_messages: Arc::new(RwLock::new(Vec::new())),
+            pending_written_files: Arc::new(RwLock::new(Vec::new())),
+            file_write_generation: Arc::new(AtomicU64::new(0)),
+            messages_since_checkpoint: Arc::new(AtomicU64::new(0)),
         })
     }
 
@@ -67,6 +79,9 @@ impl CheckpointManager {
 
         // Parse message to check for tool usage
         if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&jsonl_message) {
+            if msg.get("type").and_then(|t| t.as_str()) == Some("assistant") {
+                self.messages_since_checkpoint.fetch_add(1, Ordering::SeqCst);
+            }
             if let Some(content) = msg.get("message").and_then(|m| m.get("content")) {
                 if let Some(content_array) = content.as_array() {
                     for item in content_array {
@@ -298,6 +313,7 @@ impl CheckpointManager {
         for (_, state) in tracker.tracked_files.iter_mut() {
             state.is_modified = false;
         }
+        self.messages_since_checkpoint.store(0, Ordering::SeqCst);
 
         Ok(result)
     }
@@ -746,15 +762,120 @@ impl CheckpointManager {
         }
     }
 
+    /// Extract files written by Write/Edit/MultiEdit tool calls in a JSONL message
+    pub fn written_files(message: &str) -> Vec<String> {
+        let msg = match serde_json::from_str::<serde_json::Value>(message) {
+            Ok(msg) => msg,
+            Err(_) => return Vec::new(),
+        };
+
+        msg.get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+            .map(|content| {
+                content
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                    .filter(|item| {
+                        matches!(
+                            item.get("name")
+                                .and_then(|n| n.as_str())
+                                .unwrap_or("")
+                                .to_lowercase()
+                                .as_str(),
+                            "write" | "edit" | "multiedit"
+                        )
+                    })
+                    .filter_map(|item| {
+                        item.get("input")
+                            .and_then(|i| i.get("file_path"))
+                            .and_then(|p| p.as_str())
+                            .map(|p| p.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Schedule a checkpoint for written files, debounced so that a burst of
+    /// edits produces a single checkpoint once writes go quiet.
+    pub async fn schedule_file_write_checkpoint(self: Arc<Self>, files: Vec<String>) {
+        if files.is_empty() || !self.timeline.read().await.checkpoint_on_file_write {
+            return;
+        }
+
+        {
+            let mut pending = self.pending_written_files.write().await;
+            for file in files {
+                if !pending.contains(&file) {
+                    pending.push(file);
+                }
+            }
+        }
+
+        let generation = self.file_write_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::spawn(async move {
+            tokio::time::sleep(FILE_WRITE_DEBOUNCE).await;
+            if self.file_write_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            let files: Vec<String> = self.pending_written_files.write().await.drain(..).collect();
+            let description = match files.as_slice() {
+                [] => return,
+                [file] => format!("Auto: wrote {}", file),
+                [file, rest @ ..] => format!("Auto: wrote {} and {} more", file, rest.len()),
+            };
+
+            if let Err(e) = self.create_checkpoint(Some(description), None).await {
+                log::warn!("Failed to create file-write checkpoint: {}", e);
+            }
+        });
+    }
+
+    /// Number of assistant messages tracked since the last checkpoint
+    pub fn messages_since_checkpoint(&self) -> u64 {
+        self.messages_since_checkpoint.load(Ordering::SeqCst)
+    }
+
+    /// Create a checkpoint once `auto_checkpoint_every_n_messages` assistant messages have
+    /// been tracked since the last one. A pending file-write checkpoint is folded into it.
+    pub async fn checkpoint_if_message_threshold(&self) -> Result<Option<CheckpointResult>> {
+        let threshold = match self.timeline.read().await.auto_checkpoint_every_n_messages {
+            Some(n) if n > 0 => n as u64,
+            _ => return Ok(None),
+        };
+        let count = self.messages_since_checkpoint();
+        if count < threshold {
+            return Ok(None);
+        }
+
+        // This checkpoint covers the pending writes; cancel the debounced one
+        self.file_write_generation.fetch_add(1, Ordering::SeqCst);
+        self.pending_written_files.write().await.clear();
+
+        let description = format!("Auto: {} messages since last checkpoint", count);
+        self.create_checkpoint(Some(description), None).await.map(Some)
+    }
+
     /// Update checkpoint settings
     pub async fn update_settings(
         &self,
         auto_checkpoint_enabled: bool,
         checkpoint_strategy: CheckpointStrategy,
+        checkpoint_on_file_write: Option<bool>,
+        auto_checkpoint_every_n_messages: Option<u32>,
     ) -> Result<()> {
         let mut timeline = self.timeline.write().await;
         timeline.auto_checkpoint_enabled = auto_checkpoint_enabled;
         timeline.checkpoint_strategy = checkpoint_strategy;
+        if let Some(enabled) = checkpoint_on_file_write {
+            timeline.checkpoint_on_file_write = enabled;
+        }
+        // 0 turns the message-count trigger off
+        if let Some(n) = auto_checkpoint_every_n_messages {
+            timeline.auto_checkpoint_every_n_messages = Some(n).filter(|n| *n > 0);
+        }
 
         // Save updated timeline
         let claude_dir = self.storage.claude_dir.clone();
@@ -91,10 +91,33 @@ pub struct SessionTimeline {
     pub auto_checkpoint_enabled: bool,
     /// Strategy for automatic checkpoints
     pub checkpoint_strategy: CheckpointStrategy,
+    /// Whether to create a checkpoint whenever Claude writes or edits a file
+    #[serde(default)]
+    pub checkpoint_on_file_write: bool,
+    /// Create a checkpoint after this many assistant messages since the last one
+    #[serde(default)]
+    pub auto_checkpoint_every_n_messages: Option<u32>,
     /// Total number of checkpoints in timeline
     pub total_checkpoints: usize,
 }
 
+/// A checkpoint listed across all sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointSummary {
+    pub checkpoint_id: String,
+    pub session_id: String,
+    pub project_id: String,
+    /// Working directory of the session, when it can be determined
+    pub project_path: String,
+    /// The checkpoint's description
+    pub name: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Number of file changes captured by the checkpoint
+    pub file_count: usize,
+    pub message_index: usize,
+}
+
 /// Strategy for automatic checkpoint creation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -185,6 +208,8 @@ impl SessionTimeline {
             current_checkpoint_id: None,
             auto_checkpoint_enabled: false,
             checkpoint_strategy: CheckpointStrategy::default(),
+            checkpoint_on_file_write: false,
+            auto_checkpoint_every_n_messages: None,
             total_checkpoints: 0,
         }
     }
@@ -211,6 +236,92 @@ impl SessionTimeline {
     }
 }
 
+/// A parent/child relationship between two checkpoints in the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEdge {
+    pub parent_id: String,
+    pub child_id: String,
+    /// True when the child starts a new branch (a sibling already continues the parent)
+    pub is_fork: bool,
+}
+
+impl SessionTimeline {
+    /// All checkpoints in the timeline, in depth-first order
+    pub fn checkpoints(&self) -> Vec<&Checkpoint> {
+        fn walk<'a>(node: &'a TimelineNode, out: &mut Vec<&'a Checkpoint>) {
+            out.push(&node.checkpoint);
+            for child in &node.children {
+                walk(child, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        if let Some(root) = &self.root_node {
+            walk(root, &mut out);
+        }
+        out
+    }
+
+    /// Parent links between checkpoints; every child after the first is a fork
+    pub fn edges(&self) -> Vec<TimelineEdge> {
+        fn walk(node: &TimelineNode, out: &mut Vec<TimelineEdge>) {
+            for (index, child) in node.children.iter().enumerate() {
+                out.push(TimelineEdge {
+                    parent_id: node.checkpoint.id.clone(),
+                    child_id: child.checkpoint.id.clone(),
+                    is_fork: index > 0,
+                });
+                walk(child, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        if let Some(root) = &self.root_node {
+            walk(root, &mut out);
+        }
+        out
+    }
+
+    /// Render the timeline as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', " ");
+
+        let mut dot = format!("digraph \"{}\" {{\n", escape(&self.session_id));
+        dot.push_str("    rankdir=TB;\n");
+        dot.push_str("    node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+
+        for checkpoint in self.checkpoints() {
+            let name = checkpoint
+                .description
+                .as_deref()
+                .filter(|d| !d.is_empty())
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| checkpoint.id.chars().take(8).collect());
+            let current = self.current_checkpoint_id.as_deref() == Some(checkpoint.id.as_str());
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{}\"{}];\n",
+                escape(&checkpoint.id),
+                escape(&name),
+                checkpoint.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                if current { ", penwidth=2" } else { "" }
+            ));
+        }
+
+        for edge in self.edges() {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\"{};\n",
+                escape(&edge.parent_id),
+                escape(&edge.child_id),
+                if edge.is_fork { " [style=dashed, label=\"fork\"]" } else { "" }
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
 /// Checkpoint storage paths
 pub struct CheckpointPaths {
     pub timeline_file: PathBuf,
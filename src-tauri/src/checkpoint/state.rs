@@ -2,10 +2,16 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 use super::manager::CheckpointManager;
 
+/// Managers unused for this long are evicted by the background sweep
+pub const MANAGER_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// How often the background sweep checks for idle managers
+pub const MANAGER_EVICTION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 /// Manages checkpoint managers for active sessions
 ///
 /// This struct maintains a stateful collection of CheckpointManager instances,
@@ -16,6 +22,8 @@ pub struct CheckpointState {
     /// Map of session_id to CheckpointManager
     /// Uses Arc<CheckpointManager> to allow sharing across async boundaries
     managers: Arc<RwLock<HashMap<String, Arc<CheckpointManager>>>>,
+    /// When each session's manager was last handed out, used for idle eviction
+    last_used: Arc<RwLock<HashMap<String, Instant>>>,
     /// The Claude directory path for consistent access
     claude_dir: Arc<RwLock<Option<PathBuf>>>,
 }
@@ -25,10 +33,19 @@ impl CheckpointState {
     pub fn new() -> Self {
         Self {
             managers: Arc::new(RwLock::new(HashMap::new())),
+            last_used: Arc::new(RwLock::new(HashMap::new())),
             claude_dir: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Records that a session's manager was just used
+    async fn touch(&self, session_id: &str) {
+        self.last_used
+            .write()
+            .await
+            .insert(session_id.to_string(), Instant::now());
+    }
+
     /// Sets the Claude directory path
     ///
     /// This should be called once during application initialization
@@ -59,7 +76,10 @@ impl CheckpointState {
 
         // Check if manager already exists
         if let Some(manager) = managers.get(&session_id) {
-            return Ok(Arc::clone(manager));
+            let manager = Arc::clone(manager);
+            drop(managers);
+            self.touch(&session_id).await;
+            return Ok(manager);
         }
 
         // Get Claude directory
@@ -76,7 +96,9 @@ impl CheckpointState {
                 .await?;
 
         let manager_arc = Arc::new(manager);
-        managers.insert(session_id, Arc::clone(&manager_arc));
+        managers.insert(session_id.clone(), Arc::clone(&manager_arc));
+        drop(managers);
+        self.touch(&session_id).await;
 
         Ok(manager_arc)
     }
@@ -84,10 +106,12 @@ impl CheckpointState {
     /// Gets an existing CheckpointManager for a session
     ///
     /// Returns None if no manager exists for the session
-    #[allow(dead_code)]
     pub async fn get_manager(&self, session_id: &str) -> Option<Arc<CheckpointManager>> {
-        let managers = self.managers.read().await;
-        managers.get(session_id).map(Arc::clone)
+        let manager = self.managers.read().await.get(session_id).map(Arc::clone);
+        if manager.is_some() {
+            self.touch(session_id).await;
+        }
+        manager
     }
 
     /// Removes a CheckpointManager for a session
@@ -95,9 +119,29 @@ impl CheckpointState {
     /// This should be called when a session ends to free resources
     pub async fn remove_manager(&self, session_id: &str) -> Option<Arc<CheckpointManager>> {
         let mut managers = self.managers.write().await;
+        self.last_used.write().await.remove(session_id);
         managers.remove(session_id)
     }
 
+    /// Removes managers that haven't been used for longer than `max_idle`
+    ///
+    /// Returns the session IDs that were evicted
+    pub async fn evict_idle(&self, max_idle: Duration) -> Vec<String> {
+        let mut managers = self.managers.write().await;
+        let mut last_used = self.last_used.write().await;
+
+        let idle: Vec<String> = managers
+            .keys()
+            .filter(|id| last_used.get(*id).is_none_or(|used| used.elapsed() > max_idle))
+            .cloned()
+            .collect();
+        for session_id in &idle {
+            managers.remove(session_id);
+            last_used.remove(session_id);
+        }
+        idle
+    }
+
     /// Clears all managers
     ///
     /// This is useful for cleanup during application shutdown
@@ -105,6 +149,7 @@ impl CheckpointState {
     pub async fn clear_all(&self) {
         let mut managers = self.managers.write().await;
         managers.clear();
+        self.last_used.write().await.clear();
     }
 
     /// Gets the number of active managers
@@ -253,6 +253,36 @@ impl CheckpointStorage {
         Ok(timeline)
     }
 
+    /// Load every session timeline under `projects/*/.timelines`, paired with its project id.
+    /// Timelines that can't be read are skipped.
+    pub fn load_all_timelines(&self) -> Result<Vec<(String, SessionTimeline)>> {
+        let projects_dir = self.claude_dir.join("projects");
+        if !projects_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut timelines = Vec::new();
+        for project in fs::read_dir(&projects_dir).context("Failed to read projects directory")?.flatten() {
+            let timelines_dir = project.path().join(".timelines");
+            let sessions = match fs::read_dir(&timelines_dir) {
+                Ok(sessions) => sessions,
+                Err(_) => continue,
+            };
+            let project_id = project.file_name().to_string_lossy().to_string();
+            for session in sessions.flatten() {
+                let timeline_file = session.path().join("timeline.json");
+                if !timeline_file.is_file() {
+                    continue;
+                }
+                match self.load_timeline(&timeline_file) {
+                    Ok(timeline) => timelines.push((project_id.clone(), timeline)),
+                    Err(e) => log::warn!("Skipping timeline {:?}: {}", timeline_file, e),
+                }
+            }
+        }
+        Ok(timelines)
+    }
+
     /// Update timeline with a new checkpoint
     fn update_timeline_with_checkpoint(
         &self,
@@ -350,7 +380,7 @@ impl CheckpointStorage {
         }
 
         // Sort by timestamp (oldest first)
-        all_checkpoints.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        all_checkpoints.sort_by_key(|c| c.timestamp);
 
         // Keep only the most recent checkpoints
         let to_remove = all_checkpoints.len().saturating_sub(keep_count);
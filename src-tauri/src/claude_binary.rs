@@ -36,12 +36,13 @@ pub struct ClaudeInstallation {
 /// Checks database first for stored path and preference, then prioritizes accordingly
 pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, String> {
     info!("Searching for claude binary...");
+    let mut registered = Vec::new();
 
     // First check if we have a stored path and preference in the database
     if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
         let db_path = app_data_dir.join("agents.db");
         if db_path.exists() {
-            if let Ok(conn) = rusqlite::Connection::open(&db_path) {
+            if let Ok(conn) = crate::commands::agents::open_agent_db(&db_path) {
                 // Check for stored path first
                 if let Ok(stored_path) = conn.query_row(
                     "SELECT value FROM app_settings WHERE key = 'claude_binary_path'",
@@ -73,12 +74,16 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
                 ).unwrap_or_else(|_| "system".to_string());
 
                 info!("User preference for Claude installation: {}", preference);
+
+                registered = find_registered_installations(&conn);
             }
         }
     }
 
-    // Discover all available system installations
-    let installations = discover_system_installations();
+    // Discover all available system installations, plus user-registered custom ones
+    let mut installations = discover_system_installations();
+    installations.extend(registered);
+    dedup_installations(&mut installations);
 
     if installations.is_empty() {
         error!("Could not find claude binary in any location");
@@ -171,13 +176,45 @@ fn discover_system_installations() -> Vec<ClaudeInstallation> {
     // 4. Check standard paths
     installations.extend(find_standard_installations());
 
-    // Remove duplicates by path
-    let mut unique_paths = std::collections::HashSet::new();
-    installations.retain(|install| unique_paths.insert(install.path.clone()));
+    dedup_installations(&mut installations);
 
     installations
 }
 
+/// Custom installations registered by the user in the `claude_installations` table.
+/// Versions come from the `get_claude_version` cache, so only new or updated binaries are run.
+pub fn find_registered_installations(conn: &rusqlite::Connection) -> Vec<ClaudeInstallation> {
+    let mut stmt = match conn.prepare("SELECT label, path FROM claude_installations ORDER BY label") {
+        Ok(stmt) => stmt,
+        Err(_) => return Vec::new(),
+    };
+
+    let rows = match stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))) {
+        Ok(rows) => rows,
+        Err(_) => return Vec::new(),
+    };
+
+    rows.flatten()
+        .filter(|(_, path)| PathBuf::from(path).is_file())
+        .map(|(label, path)| ClaudeInstallation {
+            version: get_claude_version(&path).ok().flatten(),
+            path,
+            source: format!("custom:{}", label),
+            installation_type: InstallationType::Custom,
+        })
+        .collect()
+}
+
+/// Drop installations that resolve to the same file (symlinks, relative paths),
+/// keeping the first occurrence
+pub fn dedup_installations(installations: &mut Vec<ClaudeInstallation>) {
+    let mut seen = std::collections::HashSet::new();
+    installations.retain(|install| {
+        let key = std::fs::canonicalize(&install.path).unwrap_or_else(|_| PathBuf::from(&install.path));
+        seen.insert(key)
+    });
+}
+
 /// Find bundled sidecar installation
 fn find_bundled_installation() -> Option<ClaudeInstallation> {
     // The bundled sidecar is referenced by the special identifier "claude-code"
@@ -459,8 +496,48 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
     installations
 }
 
-/// Get Claude version by running --version command
-fn get_claude_version(path: &str) -> Result<Option<String>, String> {
+/// Binary mtime and the version it reported
+type CachedVersion = (std::time::SystemTime, Option<String>);
+
+/// `--version` results keyed by binary path, valid while the file's mtime is unchanged
+static VERSION_CACHE: std::sync::OnceLock<std::sync::Mutex<std::collections::HashMap<String, CachedVersion>>> =
+    std::sync::OnceLock::new();
+
+/// Get Claude version by running --version command.
+/// Results are cached per path and modification time, so repeated calls only spawn
+/// the binary again after it has been replaced or updated.
+pub fn get_claude_version(path: &str) -> Result<Option<String>, String> {
+    let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let cache = VERSION_CACHE.get_or_init(Default::default);
+    if let Some(mtime) = mtime {
+        if let Ok(cache) = cache.lock() {
+            if let Some((cached_mtime, version)) = cache.get(path) {
+                if *cached_mtime == mtime {
+                    return Ok(version.clone());
+                }
+            }
+        }
+    }
+
+    let version = probe_claude_version(path)?;
+    if let (Some(mtime), Ok(mut cache)) = (mtime, cache.lock()) {
+        cache.insert(path.to_string(), (mtime, version.clone()));
+    }
+    Ok(version)
+}
+
+/// `get_claude_version` for async callers; the first probe of a binary runs on the
+/// blocking pool instead of stalling the runtime
+pub async fn get_claude_version_async(path: &str) -> Option<String> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || get_claude_version(&path).ok().flatten())
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Run `path --version` and parse the version from its output
+fn probe_claude_version(path: &str) -> Result<Option<String>, String> {
     let mut cmd = Command::new(path);
     cmd.arg("--version");
     
@@ -487,8 +564,44 @@ fn get_claude_version(path: &str) -> Result<Option<String>, String> {
     }
 }
 
+/// Run `path --version` and check that it answers like Claude Code, e.g. "1.0.41 (Claude Code)".
+/// Returns the detected version.
+pub fn verify_claude_code_binary(path: &str) -> Result<String, String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("--version");
+
+    // On Windows, hide the console window to prevent CMD popup
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {} --version: {}", path, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} --version exited with {}; is this a Claude Code binary?",
+            path, output.status
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.to_lowercase().contains("claude code") {
+        return Err(format!(
+            "{} does not look like Claude Code (--version printed {:?})",
+            path,
+            stdout.trim()
+        ));
+    }
+    extract_version_from_output(&output.stdout)
+        .ok_or_else(|| format!("Could not find a version in {} --version output: {:?}", path, stdout.trim()))
+}
+
 /// Extract version string from command output
-fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
+pub(crate) fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
     let output_str = String::from_utf8_lossy(stdout);
 
     // Debug log the raw output
@@ -549,7 +662,7 @@ fn select_best_installation(installations: Vec<ClaudeInstallation>) -> Option<Cl
 }
 
 /// Compare two version strings
-fn compare_versions(a: &str, b: &str) -> Ordering {
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
     // Simple semantic version comparison
     let a_parts: Vec<u32> = a
         .split('.')
@@ -587,6 +700,81 @@ fn compare_versions(a: &str, b: &str) -> Ordering {
     Ordering::Equal
 }
 
+/// Shells probed on Windows, in order of preference, and whether each is POSIX-compatible
+const WINDOWS_SHELL_CANDIDATES: &[(&str, bool)] = &[
+    ("C:\\Program Files\\Git\\bin\\bash.exe", true),
+    ("C:\\Program Files (x86)\\Git\\bin\\bash.exe", true),
+    ("C:\\msys64\\usr\\bin\\bash.exe", true),
+    ("C:\\cygwin64\\bin\\bash.exe", true),
+    ("C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe", false),
+    ("powershell.exe", false),
+    ("cmd.exe", false),
+];
+
+/// First Windows shell that exists, with whether it is a POSIX shell
+pub fn find_windows_shell() -> Option<(String, bool)> {
+    WINDOWS_SHELL_CANDIDATES
+        .iter()
+        .find(|(path, _)| std::path::Path::new(path).exists())
+        .map(|(path, posix)| (path.to_string(), *posix))
+}
+
+/// Shell that spawned Claude processes will use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellInfo {
+    pub platform: String,
+    /// Shell path passed as SHELL, or None when falling back to a bare "bash"
+    pub shell_path: Option<String>,
+    pub is_posix: bool,
+    /// Set when Claude CLI is likely to have trouble with the detected shell
+    pub recommendation: Option<String>,
+}
+
+/// Detect the shell the same way `create_command_with_env` does
+pub fn detect_shell() -> ShellInfo {
+    let platform = std::env::consts::OS.to_string();
+
+    if cfg!(target_os = "windows") {
+        return match find_windows_shell() {
+            Some((path, true)) => ShellInfo {
+                platform,
+                shell_path: Some(path),
+                is_posix: true,
+                recommendation: None,
+            },
+            Some((path, false)) => ShellInfo {
+                platform,
+                shell_path: Some(path),
+                is_posix: false,
+                recommendation: Some(
+                    "Only cmd/PowerShell was found. Claude CLI needs a POSIX shell on Windows; install Git for Windows (Git Bash) and restart the app.".to_string(),
+                ),
+            },
+            None => ShellInfo {
+                platform,
+                shell_path: None,
+                is_posix: false,
+                recommendation: Some(
+                    "No shell was found; SHELL falls back to \"bash\", which may not exist. Install Git for Windows (Git Bash).".to_string(),
+                ),
+            },
+        };
+    }
+
+    // Elsewhere the inherited SHELL is passed through unchanged
+    let shell_path = std::env::var("SHELL").ok().filter(|s| !s.is_empty());
+    ShellInfo {
+        platform,
+        is_posix: true,
+        recommendation: if shell_path.is_none() {
+            Some("SHELL is not set; Claude CLI will fall back to /bin/sh.".to_string())
+        } else {
+            None
+        },
+        shell_path,
+    }
+}
+
 /// Helper function to create a Command with proper environment variables
 /// This ensures commands like Claude can find Node.js and other dependencies
 pub fn create_command_with_env(program: &str) -> Command {
@@ -640,30 +828,16 @@ pub fn create_command_with_env(program: &str) -> Command {
     // On Windows, ensure SHELL environment variable is set for Claude CLI
     if cfg!(target_os = "windows") {
         // Always set SHELL environment variable on Windows for Claude CLI compatibility
-        let shell_candidates = [
-            "C:\\Program Files\\Git\\bin\\bash.exe",
-            "C:\\Program Files (x86)\\Git\\bin\\bash.exe",
-            "C:\\msys64\\usr\\bin\\bash.exe",
-            "C:\\cygwin64\\bin\\bash.exe",
-            "C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe",
-            "powershell.exe",
-            "cmd.exe"
-        ];
-
-        let mut shell_found = false;
-        for shell_path in &shell_candidates {
-            if std::path::Path::new(shell_path).exists() {
+        match find_windows_shell() {
+            Some((shell_path, _)) => {
                 debug!("Setting SHELL environment variable for Windows: {}", shell_path);
                 cmd.env("SHELL", shell_path);
-                shell_found = true;
-                break;
             }
-        }
-
-        // If no shell found, default to bash (Claude CLI prefers POSIX shells)
-        if !shell_found {
-            debug!("No suitable shell found, defaulting to bash for Claude CLI compatibility");
-            cmd.env("SHELL", "bash");
+            None => {
+                // If no shell found, default to bash (Claude CLI prefers POSIX shells)
+                debug!("No suitable shell found, defaulting to bash for Claude CLI compatibility");
+                cmd.env("SHELL", "bash");
+            }
         }
 
         // Also set other Windows-specific environment variables that Claude CLI might need
@@ -0,0 +1,258 @@
+use chrono::{DateTime, Utc};
+use log::debug;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use super::agents::{parse_db_timestamp, AgentDb};
+use crate::checkpoint::{SessionTimeline, TimelineNode};
+
+/// Default and maximum number of entries returned by `get_activity_feed`
+const DEFAULT_FEED_LIMIT: usize = 50;
+const MAX_FEED_LIMIT: usize = 500;
+
+/// What kind of event an activity feed entry describes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    AgentRunStarted,
+    AgentRunFinished,
+    /// A session transcript was last written (its JSONL mtime)
+    SessionActivity,
+    CheckpointCreated,
+}
+
+/// One entry of the activity feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    /// RFC 3339; pass the last entry's timestamp as `before` to get the next page
+    pub timestamp: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    pub project_path: Option<String>,
+    pub session_id: Option<String>,
+    pub run_id: Option<i64>,
+    pub checkpoint_id: Option<String>,
+    /// Final status for finished agent runs
+    pub status: Option<String>,
+}
+
+impl ActivityEvent {
+    fn new(kind: ActivityKind, timestamp: DateTime<Utc>, title: String) -> Self {
+        Self {
+            kind,
+            timestamp: timestamp.to_rfc3339(),
+            title,
+            project_id: None,
+            project_path: None,
+            session_id: None,
+            run_id: None,
+            checkpoint_id: None,
+            status: None,
+        }
+    }
+}
+
+/// Agent run start and finish events older than `before`
+fn agent_run_events(
+    db: &AgentDb,
+    before: Option<DateTime<Utc>>,
+    limit: usize,
+) -> Result<Vec<(DateTime<Utc>, ActivityEvent)>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    // Stored timestamps are `YYYY-MM-DD HH:MM:SS` UTC, which compare correctly as text
+    let cursor = before
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "9999-12-31 23:59:59".to_string());
+
+    let mut events = Vec::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_name, task, project_path, session_id, created_at FROM agent_runs
+             WHERE created_at < ?1 ORDER BY created_at DESC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let started = stmt
+        .query_map(params![cursor, limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    for row in started {
+        let (run_id, agent_name, task, project_path, session_id, created_at) =
+            row.map_err(|e| e.to_string())?;
+        let timestamp = match parse_db_timestamp(&created_at) {
+            Some(timestamp) => timestamp,
+            None => continue,
+        };
+        let mut event = ActivityEvent::new(
+            ActivityKind::AgentRunStarted,
+            timestamp,
+            format!("{} started: {}", agent_name, task.lines().next().unwrap_or_default()),
+        );
+        event.run_id = Some(run_id);
+        event.project_path = Some(project_path);
+        event.session_id = Some(session_id).filter(|id| !id.is_empty());
+        events.push((timestamp, event));
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_name, status, project_path, session_id, completed_at FROM agent_runs
+             WHERE completed_at IS NOT NULL AND completed_at < ?1 ORDER BY completed_at DESC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let finished = stmt
+        .query_map(params![cursor, limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    for row in finished {
+        let (run_id, agent_name, status, project_path, session_id, completed_at) =
+            row.map_err(|e| e.to_string())?;
+        let timestamp = match parse_db_timestamp(&completed_at) {
+            Some(timestamp) => timestamp,
+            None => continue,
+        };
+        let mut event = ActivityEvent::new(
+            ActivityKind::AgentRunFinished,
+            timestamp,
+            format!("{} {}", agent_name, status),
+        );
+        event.run_id = Some(run_id);
+        event.project_path = Some(project_path);
+        event.session_id = Some(session_id).filter(|id| !id.is_empty());
+        event.status = Some(status);
+        events.push((timestamp, event));
+    }
+
+    Ok(events)
+}
+
+/// Collect every checkpoint in a timeline tree
+fn collect_checkpoints(node: &TimelineNode, out: &mut Vec<crate::checkpoint::Checkpoint>) {
+    out.push(node.checkpoint.clone());
+    for child in &node.children {
+        collect_checkpoints(child, out);
+    }
+}
+
+/// Session and checkpoint events for every project under `~/.claude/projects`
+fn project_events(projects_dir: &Path, before: Option<DateTime<Utc>>) -> Vec<(DateTime<Utc>, ActivityEvent)> {
+    let mut events = Vec::new();
+    let is_before = |t: &DateTime<Utc>| before.is_none_or(|before| *t < before);
+
+    let projects = match std::fs::read_dir(projects_dir) {
+        Ok(projects) => projects,
+        Err(_) => return events,
+    };
+    for project in projects.flatten() {
+        let project_dir = project.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let project_id = project.file_name().to_string_lossy().to_string();
+
+        for entry in std::fs::read_dir(&project_dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let timestamp = match entry.metadata().and_then(|m| m.modified()) {
+                Ok(modified) => DateTime::<Utc>::from(modified),
+                Err(_) => continue,
+            };
+            if !is_before(&timestamp) {
+                continue;
+            }
+            let session_id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut event = ActivityEvent::new(
+                ActivityKind::SessionActivity,
+                timestamp,
+                format!("Session {}", session_id),
+            );
+            event.project_id = Some(project_id.clone());
+            event.session_id = Some(session_id);
+            events.push((timestamp, event));
+        }
+
+        let timelines_dir = project_dir.join(".timelines");
+        for entry in std::fs::read_dir(&timelines_dir).into_iter().flatten().flatten() {
+            let timeline_file = entry.path().join("timeline.json");
+            let timeline = match std::fs::read_to_string(&timeline_file)
+                .ok()
+                .and_then(|json| serde_json::from_str::<SessionTimeline>(&json).ok())
+            {
+                Some(timeline) => timeline,
+                None => continue,
+            };
+            let mut checkpoints = Vec::new();
+            if let Some(root) = &timeline.root_node {
+                collect_checkpoints(root, &mut checkpoints);
+            }
+            for checkpoint in checkpoints {
+                if !is_before(&checkpoint.timestamp) {
+                    continue;
+                }
+                let title = checkpoint
+                    .description
+                    .clone()
+                    .filter(|d| !d.is_empty())
+                    .unwrap_or_else(|| format!("Checkpoint at message {}", checkpoint.message_index));
+                let mut event =
+                    ActivityEvent::new(ActivityKind::CheckpointCreated, checkpoint.timestamp, title);
+                event.project_id = Some(project_id.clone());
+                event.session_id = Some(checkpoint.session_id);
+                event.checkpoint_id = Some(checkpoint.id);
+                events.push((checkpoint.timestamp, event));
+            }
+        }
+    }
+
+    events
+}
+
+/// Recent agent runs, session activity and checkpoints merged into one list, newest first.
+/// Pass the last entry's timestamp as `before` to page backwards.
+#[tauri::command]
+pub async fn get_activity_feed(
+    db: State<'_, AgentDb>,
+    limit: Option<usize>,
+    before: Option<String>,
+) -> Result<Vec<ActivityEvent>, String> {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).clamp(1, MAX_FEED_LIMIT);
+    let before = before
+        .map(|b| {
+            DateTime::parse_from_rfc3339(&b)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| format!("Invalid 'before' timestamp '{}': {}", b, e))
+        })
+        .transpose()?;
+
+    let mut events = agent_run_events(&db, before, limit)?;
+    match super::claude::get_claude_dir() {
+        Ok(claude_dir) => events.extend(project_events(&claude_dir.join("projects"), before)),
+        Err(e) => debug!("Skipping session activity: {}", e),
+    }
+
+    events.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    Ok(events.into_iter().take(limit).map(|(_, event)| event).collect())
+}
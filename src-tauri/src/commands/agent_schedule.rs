@@ -0,0 +1,295 @@
+use chrono::{DateTime, Local, Utc};
+use log::{debug, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use super::agent_watch::is_run_active;
+use super::agents::{execute_agent, get_agent, parse_db_timestamp, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// How often the scheduler checks for due schedules
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+
+/// A recurring agent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledAgentRun {
+    pub id: Option<i64>,
+    pub agent_id: i64,
+    /// Standard 5-field cron (`min hour day month weekday`) in local time; 6/7-field with seconds also accepted
+    pub cron_expression: String,
+    pub project_path: String,
+    /// Falls back to the agent's default task when empty
+    pub task: Option<String>,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_run_id: Option<i64>,
+    pub created_at: String,
+    /// Next time this schedule fires, if enabled
+    #[serde(default)]
+    pub next_run_at: Option<String>,
+}
+
+/// Parse a cron expression; 5-field expressions get a leading seconds field
+fn parse_cron(expression: &str) -> Result<cron::Schedule, String> {
+    let expression = expression.trim();
+    let normalized = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))
+}
+
+/// The first firing of `schedule` after `since`
+fn next_fire_after(schedule: &cron::Schedule, since: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    schedule
+        .after(&since.with_timezone(&Local))
+        .next()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Timestamp in the format SQLite's CURRENT_TIMESTAMP uses
+fn db_timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn row_to_schedule(row: &rusqlite::Row) -> rusqlite::Result<ScheduledAgentRun> {
+    let mut schedule = ScheduledAgentRun {
+        id: Some(row.get(0)?),
+        agent_id: row.get(1)?,
+        cron_expression: row.get(2)?,
+        project_path: row.get(3)?,
+        task: row.get(4)?,
+        enabled: row.get(5)?,
+        last_run_at: row.get(6)?,
+        last_run_id: row.get(7)?,
+        created_at: row.get(8)?,
+        next_run_at: None,
+    };
+    if schedule.enabled {
+        let since = schedule
+            .last_run_at
+            .as_deref()
+            .or(Some(schedule.created_at.as_str()))
+            .and_then(parse_db_timestamp)
+            .unwrap_or_else(Utc::now);
+        schedule.next_run_at = parse_cron(&schedule.cron_expression)
+            .ok()
+            .and_then(|cron| next_fire_after(&cron, since))
+            .map(|t| t.to_rfc3339());
+    }
+    Ok(schedule)
+}
+
+const SCHEDULE_COLUMNS: &str =
+    "id, agent_id, cron_expression, project_path, task, enabled, last_run_at, last_run_id, created_at";
+
+fn get_schedule(conn: &Connection, id: i64) -> Result<ScheduledAgentRun, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM scheduled_agent_runs WHERE id = ?1", SCHEDULE_COLUMNS),
+        params![id],
+        row_to_schedule,
+    )
+    .map_err(|e| format!("Scheduled run not found: {}", e))
+}
+
+/// Create a recurring run for an agent
+#[tauri::command]
+pub async fn create_scheduled_agent_run(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    cron_expression: String,
+    project_path: String,
+    task: Option<String>,
+    enabled: Option<bool>,
+) -> Result<ScheduledAgentRun, String> {
+    parse_cron(&cron_expression)?;
+    let agent = get_agent(db.clone(), agent_id).await?;
+    let task = task.filter(|t| !t.trim().is_empty());
+    if task.is_none() && agent.default_task.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        return Err(format!("Agent '{}' has no default task; a task is required", agent.name));
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO scheduled_agent_runs (agent_id, cron_expression, project_path, task, enabled) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![agent_id, cron_expression.trim(), project_path, task, enabled.unwrap_or(true)],
+    )
+    .map_err(|e| e.to_string())?;
+    let schedule = get_schedule(&conn, conn.last_insert_rowid())?;
+    info!(
+        "Scheduled agent {} with '{}' (next run {:?})",
+        agent_id, schedule.cron_expression, schedule.next_run_at
+    );
+    Ok(schedule)
+}
+
+/// List recurring runs, optionally for one agent
+#[tauri::command]
+pub async fn list_scheduled_agent_runs(
+    db: State<'_, AgentDb>,
+    agent_id: Option<i64>,
+) -> Result<Vec<ScheduledAgentRun>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM scheduled_agent_runs WHERE ?1 IS NULL OR agent_id = ?1 ORDER BY created_at DESC",
+            SCHEDULE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let schedules = stmt
+        .query_map(params![agent_id], row_to_schedule)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(schedules)
+}
+
+/// Update a recurring run; omitted fields are left unchanged and an empty task clears it
+#[tauri::command]
+pub async fn update_scheduled_agent_run(
+    db: State<'_, AgentDb>,
+    id: i64,
+    cron_expression: Option<String>,
+    project_path: Option<String>,
+    task: Option<String>,
+    enabled: Option<bool>,
+) -> Result<ScheduledAgentRun, String> {
+    if let Some(expression) = &cron_expression {
+        parse_cron(expression)?;
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let current = get_schedule(&conn, id)?;
+    let task = match task {
+        Some(task) => Some(task).filter(|t| !t.trim().is_empty()),
+        None => current.task,
+    };
+    conn.execute(
+        "UPDATE scheduled_agent_runs SET cron_expression = ?1, project_path = ?2, task = ?3, enabled = ?4 WHERE id = ?5",
+        params![
+            cron_expression.map(|c| c.trim().to_string()).unwrap_or(current.cron_expression),
+            project_path.unwrap_or(current.project_path),
+            task,
+            enabled.unwrap_or(current.enabled),
+            id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    get_schedule(&conn, id)
+}
+
+/// Delete a recurring run; runs it already started are kept
+#[tauri::command]
+pub async fn delete_scheduled_agent_run(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM scheduled_agent_runs WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Scheduled run not found: {}", id));
+    }
+    Ok(())
+}
+
+/// Start every schedule that is due. A schedule whose previous run is still active
+/// skips this trigger; missed triggers while the app was closed fire once at most.
+async fn run_due_schedules(app: &AppHandle) -> Result<(), String> {
+    let now = Utc::now();
+    let due: Vec<ScheduledAgentRun> = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM scheduled_agent_runs WHERE enabled = 1",
+                SCHEDULE_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let schedules = stmt
+            .query_map([], row_to_schedule)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        schedules
+            .into_iter()
+            .filter(|s| {
+                s.next_run_at
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .is_some_and(|next| next <= now)
+            })
+            .collect()
+    };
+
+    for schedule in due {
+        let schedule_id = schedule.id.unwrap_or_default();
+
+        let previous_active = schedule.last_run_id.is_some_and(|run_id| is_run_active(app, run_id));
+        let run_id = if previous_active {
+            info!("Scheduled run {}: previous run still active, skipping", schedule_id);
+            schedule.last_run_id
+        } else {
+            let task = match schedule.task.clone() {
+                Some(task) => task,
+                None => match get_agent(app.state::<AgentDb>(), schedule.agent_id).await {
+                    Ok(agent) => agent.default_task.unwrap_or_default(),
+                    Err(e) => {
+                        warn!("Scheduled run {}: {}", schedule_id, e);
+                        continue;
+                    }
+                },
+            };
+            let result = execute_agent(
+                app.clone(),
+                schedule.agent_id,
+                Some(schedule.project_path.clone()),
+                task,
+                None,
+                None,
+                None,
+                None,
+                None,
+                app.state::<AgentDb>(),
+                app.state::<ProcessRegistryState>(),
+            )
+            .await;
+            match result {
+                Ok(run_id) => {
+                    info!("Scheduled run {} started agent run {}", schedule_id, run_id);
+                    Some(run_id)
+                }
+                Err(e) => {
+                    warn!("Scheduled run {} failed to start: {}", schedule_id, e);
+                    schedule.last_run_id
+                }
+            }
+        };
+
+        // Record the trigger even when skipped so it isn't retried on the next tick
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE scheduled_agent_runs SET last_run_at = ?1, last_run_id = ?2 WHERE id = ?3",
+            params![db_timestamp(now), run_id, schedule_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Background loop that triggers scheduled agent runs; started once at app setup
+pub fn start_agent_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_schedules(&app).await {
+                debug!("Agent scheduler tick failed: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,236 @@
+use log::{debug, info, warn};
+use rusqlite::params;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::agents::AgentDb;
+use crate::process::{ProcessRegistryState, RunOutputEvent};
+
+/// Interval between keep-alive comments on idle streams
+const SSE_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Longest request or header line accepted
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// Most header lines read before the request is rejected
+const MAX_HEADER_LINES: usize = 64;
+
+/// The running SSE server: bound port and accept loop
+#[derive(Default)]
+pub struct AgentSseServerState(Mutex<Option<(u16, tokio::task::JoinHandle<()>)>>);
+
+/// Extract the run id from a `GET /runs/{run_id}/events` request line
+fn parse_run_events_request(request_line: &str) -> Option<i64> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let path = parts.next()?.split('?').next()?;
+    path.strip_prefix("/runs/")?
+        .strip_suffix("/events")?
+        .parse()
+        .ok()
+}
+
+/// Read one line of at most `MAX_LINE_BYTES`; longer lines are an error
+async fn read_bounded_line<R: AsyncBufRead + Unpin>(reader: &mut R, line: &mut String) -> std::io::Result<usize> {
+    let read = reader.take(MAX_LINE_BYTES).read_line(line).await?;
+    if read as u64 == MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Err(std::io::Error::other("request line too long"));
+    }
+    Ok(read)
+}
+
+/// Only loopback host names are served, so a web page can't reach the server
+/// through DNS rebinding
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim();
+    let name = if let Some(rest) = host.strip_prefix('[') {
+        rest.split(']').next().unwrap_or_default()
+    } else {
+        host.split(':').next().unwrap_or_default()
+    };
+    matches!(name.to_ascii_lowercase().as_str(), "127.0.0.1" | "localhost" | "::1")
+}
+
+/// Current status of a run, or None if it doesn't exist
+fn run_status(app: &AppHandle, run_id: i64) -> Option<String> {
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().ok()?;
+    conn.query_row(
+        "SELECT status FROM agent_runs WHERE id = ?1",
+        params![run_id],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+}
+
+/// Write one SSE message; multi-line payloads become multiple `data:` lines
+async fn write_sse_event<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    event: Option<&str>,
+    data: &str,
+) -> std::io::Result<()> {
+    let mut message = String::new();
+    if let Some(event) = event {
+        message.push_str(&format!("event: {}\n", event));
+    }
+    for line in data.split('\n') {
+        message.push_str(&format!("data: {}\n", line.trim_end_matches('\r')));
+    }
+    message.push('\n');
+    writer.write_all(message.as_bytes()).await?;
+    writer.flush().await
+}
+
+async fn write_not_found<W: AsyncWrite + Unpin>(writer: &mut W) -> std::io::Result<()> {
+    writer
+        .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        .await
+}
+
+/// Serve a single HTTP connection
+async fn handle_sse_connection(app: AppHandle, mut stream: TcpStream) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.split();
+    let mut reader = BufReader::new(reader);
+
+    let mut request_line = String::new();
+    read_bounded_line(&mut reader, &mut request_line).await?;
+    // Only the Host header is needed; read until the blank line
+    let mut host = None;
+    for _ in 0..=MAX_HEADER_LINES {
+        let mut header = String::new();
+        if read_bounded_line(&mut reader, &mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    if !host.as_deref().is_some_and(is_loopback_host) {
+        writer
+            .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let run_id = match parse_run_events_request(&request_line) {
+        Some(run_id) => run_id,
+        None => return write_not_found(&mut writer).await,
+    };
+
+    // Subscribe before reading the status or replaying, so a run finishing in
+    // between still delivers its Finished event
+    let registry = app.state::<ProcessRegistryState>().0.clone();
+    let (snapshot, mut events) = registry
+        .subscribe_output(run_id)
+        .map_err(std::io::Error::other)?;
+    let status = match run_status(&app, run_id) {
+        Some(status) => status,
+        None => return write_not_found(&mut writer).await,
+    };
+
+    writer
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+
+    for line in snapshot.lines() {
+        write_sse_event(&mut writer, None, line).await?;
+    }
+
+    if !matches!(status.as_str(), "queued" | "pending" | "running") {
+        return write_sse_event(&mut writer, Some("end"), &status).await;
+    }
+
+    let mut keep_alive = tokio::time::interval(SSE_KEEP_ALIVE);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(RunOutputEvent::Line { run_id: id, line }) if id == run_id => {
+                    write_sse_event(&mut writer, None, &line).await?;
+                }
+                Ok(RunOutputEvent::Finished { run_id: id, status }) if id == run_id => {
+                    return write_sse_event(&mut writer, Some("end"), &status).await;
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    writer
+                        .write_all(format!(": {} events dropped\n\n", skipped).as_bytes())
+                        .await?;
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            _ = keep_alive.tick() => {
+                writer.write_all(b": keep-alive\n\n").await?;
+            }
+        }
+    }
+}
+
+/// Serve agent output as server-sent events on `127.0.0.1:{port}` at `GET /runs/{run_id}/events`.
+/// The live buffer is replayed first, then new lines stream until an `end` event.
+/// Pass port 0 to pick a free port. Returns the bound port.
+#[tauri::command]
+pub async fn start_agent_sse_server(
+    app: AppHandle,
+    server_state: State<'_, AgentSseServerState>,
+    port: u16,
+) -> Result<u16, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind SSE server on port {}: {}", port, e))?;
+    let bound_port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let accept_loop = tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, addr)) => {
+                    debug!("SSE client connected from {}", addr);
+                    let app = app.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_sse_connection(app, stream).await {
+                            debug!("SSE connection closed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept SSE connection: {}", e),
+            }
+        }
+    });
+
+    let previous = server_state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replace((bound_port, accept_loop));
+    if let Some((old_port, old_loop)) = previous {
+        old_loop.abort();
+        info!("Replaced agent SSE server on port {}", old_port);
+    }
+
+    info!("Agent SSE server listening on 127.0.0.1:{}", bound_port);
+    Ok(bound_port)
+}
+
+/// Stop the SSE server; returns false if it wasn't running
+#[tauri::command]
+pub async fn stop_agent_sse_server(server_state: State<'_, AgentSseServerState>) -> Result<bool, String> {
+    let server = server_state.0.lock().map_err(|e| e.to_string())?.take();
+    match server {
+        Some((port, accept_loop)) => {
+            accept_loop.abort();
+            info!("Stopped agent SSE server on port {}", port);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
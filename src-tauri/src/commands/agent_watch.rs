@@ -0,0 +1,232 @@
+use log::{info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::{execute_agent, get_agent, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// Quiet period after the last change before a batch triggers a run
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// A running directory watch that triggers an agent
+struct AgentWatch {
+    // Dropping the watcher stops file notifications
+    _watcher: notify::RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+/// Active agent watches keyed by watch id
+#[derive(Default)]
+pub struct AgentWatchState(Mutex<HashMap<String, AgentWatch>>);
+
+/// Emitted as `agent-watch:{watch_id}` for every debounced batch of changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentWatchEvent {
+    pub watch_id: String,
+    /// "triggered", "skipped" or "failed"
+    pub status: String,
+    pub run_id: Option<i64>,
+    pub changed_files: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Directories whose changes never trigger a watch: VCS metadata, Claude's own files
+/// and dependency/build output that churns during installs and builds
+const WATCH_EXCLUDED_DIRS: &[&str] = &[".git", ".claude", "node_modules", "target"];
+
+/// Whether a changed path should trigger the watch
+fn matches_watch_globs(root: &Path, path: &Path, patterns: &[glob::Pattern]) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    if relative
+        .components()
+        .any(|c| WATCH_EXCLUDED_DIRS.iter().any(|dir| c.as_os_str() == *dir))
+    {
+        return false;
+    }
+    patterns.is_empty() || patterns.iter().any(|p| p.matches_path(relative))
+}
+
+/// Whether a run is still queued, pending or running
+pub(crate) fn is_run_active(app: &AppHandle, run_id: i64) -> bool {
+    let db = app.state::<AgentDb>();
+    let conn = match db.0.lock() {
+        Ok(conn) => conn,
+        Err(_) => return false,
+    };
+    conn.query_row(
+        "SELECT status FROM agent_runs WHERE id = ?1",
+        params![run_id],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|status| matches!(status.as_str(), "queued" | "pending" | "running"))
+    .unwrap_or(false)
+}
+
+/// Debounce change notifications and run the agent once per batch
+async fn run_watch_loop(
+    app: AppHandle,
+    watch_id: String,
+    agent_id: i64,
+    project_path: String,
+    task: String,
+    mut changes: tokio::sync::mpsc::UnboundedReceiver<PathBuf>,
+) {
+    let mut last_run_id: Option<i64> = None;
+
+    while let Some(first) = changes.recv().await {
+        let mut changed = BTreeSet::from([first]);
+        loop {
+            match tokio::time::timeout(WATCH_DEBOUNCE, changes.recv()).await {
+                Ok(Some(path)) => {
+                    changed.insert(path);
+                }
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        let changed_files: Vec<String> = changed
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        let mut event = AgentWatchEvent {
+            watch_id: watch_id.clone(),
+            status: "skipped".to_string(),
+            run_id: last_run_id,
+            changed_files,
+            error: None,
+        };
+
+        // Never start a new run while the previous one from this watch is still going
+        if last_run_id.is_some_and(|run_id| is_run_active(&app, run_id)) {
+            info!("Agent watch {}: previous run still active, skipping", watch_id);
+            let _ = app.emit(&format!("agent-watch:{}", watch_id), &event);
+            continue;
+        }
+
+        let result = execute_agent(
+            app.clone(),
+            agent_id,
+            Some(project_path.clone()),
+            task.clone(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            app.state::<AgentDb>(),
+            app.state::<ProcessRegistryState>(),
+        )
+        .await;
+
+        match result {
+            Ok(run_id) => {
+                info!("Agent watch {} triggered run {}", watch_id, run_id);
+                last_run_id = Some(run_id);
+                event.status = "triggered".to_string();
+                event.run_id = Some(run_id);
+            }
+            Err(e) => {
+                warn!("Agent watch {} failed to start run: {}", watch_id, e);
+                event.status = "failed".to_string();
+                event.error = Some(e);
+            }
+        }
+        let _ = app.emit(&format!("agent-watch:{}", watch_id), &event);
+    }
+}
+
+/// Watch a project directory and run the agent's default task whenever matching files change.
+/// An empty `watch_globs` matches every file. Returns the watch id.
+#[tauri::command]
+pub async fn start_agent_watch(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    watch_state: State<'_, AgentWatchState>,
+    agent_id: i64,
+    project_path: String,
+    watch_globs: Vec<String>,
+) -> Result<String, String> {
+    let agent = get_agent(db, agent_id).await?;
+    let task = agent
+        .default_task
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| format!("Agent '{}' has no default task to run", agent.name))?;
+
+    let root = PathBuf::from(&project_path);
+    if !root.is_dir() {
+        return Err(format!("Project path is not a directory: {}", project_path));
+    }
+
+    let patterns = watch_globs
+        .iter()
+        .map(|g| glob::Pattern::new(g).map_err(|e| format!("Invalid glob '{}': {}", g, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let watch_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let event = match res {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("File watch error: {}", e);
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            if matches_watch_globs(&watch_root, &path, &patterns) {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", project_path, e))?;
+
+    let watch_id = uuid::Uuid::new_v4().to_string();
+    let task = tokio::spawn(run_watch_loop(
+        app.clone(),
+        watch_id.clone(),
+        agent_id,
+        project_path.clone(),
+        task,
+        rx,
+    ));
+
+    watch_state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(watch_id.clone(), AgentWatch { _watcher: watcher, task });
+
+    info!("Started agent watch {} for agent {} on {}", watch_id, agent_id, project_path);
+    Ok(watch_id)
+}
+
+/// Stop a watch started with `start_agent_watch`
+#[tauri::command]
+pub async fn stop_agent_watch(
+    watch_state: State<'_, AgentWatchState>,
+    watch_id: String,
+) -> Result<(), String> {
+    let watch = watch_state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&watch_id)
+        .ok_or_else(|| format!("Agent watch not found: {}", watch_id))?;
+
+    watch.task.abort();
+    info!("Stopped agent watch {}", watch_id);
+    Ok(())
+}
@@ -59,6 +59,36 @@ pub struct Session {
     pub message_timestamp: Option<String>,
 }
 
+/// A session enriched with preview information for list views
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionWithMeta {
+    #[serde(flatten)]
+    pub session: Session,
+    /// Number of user/assistant messages in the session
+    pub message_count: usize,
+    /// Most recent model used in the session (if detected)
+    pub model: Option<String>,
+    /// Unix timestamp when the session file was last modified
+    pub last_modified: u64,
+}
+
+/// Per-file metadata computed from a session JSONL, cached by mtime
+#[derive(Debug, Clone)]
+struct SessionFileMeta {
+    first_message: Option<String>,
+    message_timestamp: Option<String>,
+    message_count: usize,
+    model: Option<String>,
+}
+
+/// Cache of session metadata keyed by JSONL path and its modification time
+#[derive(Default)]
+pub struct SessionMetaCache(std::sync::Mutex<std::collections::HashMap<PathBuf, (SystemTime, SessionFileMeta)>>);
+
+/// Cache of per-project session counts keyed by project directory and its modification time
+#[derive(Default)]
+pub struct ProjectSessionCountCache(std::sync::Mutex<std::collections::HashMap<PathBuf, (SystemTime, usize)>>);
+
 /// Represents a message entry in the JSONL file
 #[derive(Debug, Deserialize)]
 struct JsonlEntry {
@@ -102,6 +132,26 @@ pub struct ClaudeVersionStatus {
     pub output: String,
 }
 
+/// Whether Claude CLI is authenticated, and how
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthStatus {
+    pub logged_in: bool,
+    /// "environment", "api_key_helper", "oauth", "api_key" or "none"
+    pub method: String,
+    /// Account details recorded by an interactive login
+    pub account_email: Option<String>,
+    pub organization: Option<String>,
+    pub subscription_type: Option<String>,
+    /// The stored OAuth access token has expired (the CLI refreshes it on next use)
+    pub oauth_expired: bool,
+    /// Names of the auth-related variables that are set; values are never returned
+    pub api_key_env_vars: Vec<String>,
+    pub binary_path: Option<String>,
+    /// Whether the bundled sidecar is the binary in use
+    pub uses_sidecar: bool,
+    pub message: String,
+}
+
 /// Represents a CLAUDE.md file found in the project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeMdFile {
@@ -145,6 +195,43 @@ pub fn get_claude_dir() -> Result<PathBuf> {
         .context("Could not find ~/.claude directory")
 }
 
+/// Canonicalizes a project path: expands `~`, resolves `.`/`..` and symlinks,
+/// drops trailing separators and checks that it is an existing directory.
+/// Keeps session files in the directory Claude Code derives from the same path.
+pub(crate) fn canonical_project_path(path: &str) -> Result<String, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Project path cannot be empty".to_string());
+    }
+
+    let expanded = if trimmed == "~" || trimmed.starts_with("~/") || trimmed.starts_with("~\\") {
+        let home = dirs::home_dir().ok_or("Could not find home directory")?;
+        home.join(trimmed[1..].trim_start_matches(['/', '\\']))
+    } else {
+        PathBuf::from(trimmed)
+    };
+
+    let canonical = expanded
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path '{}': {}", path, e))?;
+    if !canonical.is_dir() {
+        return Err(format!("Project path is not a directory: {}", path));
+    }
+
+    let mut normalized = canonical.to_string_lossy().to_string();
+    // canonicalize() returns verbatim paths on Windows, which Claude Code doesn't use
+    if let Some(stripped) = normalized.strip_prefix(r"\\?\") {
+        normalized = stripped.to_string();
+    }
+    Ok(normalized)
+}
+
+/// Validates a project path and returns its canonical form
+#[tauri::command]
+pub async fn normalize_project_path(path: String) -> Result<String, String> {
+    canonical_project_path(&path)
+}
+
 /// Gets the actual project path by reading the cwd from the first JSONL entry
 fn get_project_path_from_sessions(project_dir: &PathBuf) -> Result<String, String> {
     // Try to read any JSONL file in the directory
@@ -174,6 +261,42 @@ fn get_project_path_from_sessions(project_dir: &PathBuf) -> Result<String, Strin
     Err("Could not determine project path from session files".to_string())
 }
 
+/// Finds the project path for a session by locating its JSONL file under ~/.claude/projects
+pub(crate) fn find_session_project_path(session_id: &str) -> Result<String, String> {
+    let projects_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects");
+
+    let entries = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    for entry in entries.flatten() {
+        let project_dir = entry.path();
+        if project_dir.join(format!("{}.jsonl", session_id)).exists() {
+            return get_project_path_from_sessions(&project_dir).or_else(|_| {
+                let dir_name = project_dir
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+                Ok(decode_project_path(dir_name))
+            });
+        }
+    }
+
+    Err(format!("Session not found: {}", session_id))
+}
+
+/// Locates a session's JSONL file in any project directory
+pub(crate) fn find_session_file(session_id: &str) -> Result<PathBuf, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?
+        .flatten()
+        .map(|entry| entry.path().join(format!("{}.jsonl", session_id)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("Session not found: {}", session_id))
+}
+
 /// Decodes a project directory name back to its original path
 /// The directory names in ~/.claude/projects are encoded paths
 /// DEPRECATED: Use get_project_path_from_sessions instead when possible
@@ -595,12 +718,217 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
     }
 
     // Sort projects by creation time (newest first)
-    projects.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    projects.sort_by_key(|p| std::cmp::Reverse(p.created_at));
 
     log::info!("Found {} projects", projects.len());
     Ok(projects)
 }
 
+/// Count the session JSONL files directly inside a project directory
+fn count_project_sessions(project_dir: &std::path::Path) -> usize {
+    fs::read_dir(project_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl")
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Gets the number of sessions in each project, keyed by project ID.
+/// Counts are cached per directory and only recomputed when the directory's
+/// modification time changes (adding or removing a session updates it).
+#[tauri::command]
+pub async fn get_project_session_counts(
+    cache: tauri::State<'_, ProjectSessionCountCache>,
+) -> Result<std::collections::HashMap<String, usize>, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    let mut counts = std::collections::HashMap::new();
+    if !projects_dir.exists() {
+        return Ok(counts);
+    }
+
+    let entries = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    let mut cache = cache.0.lock().map_err(|e| e.to_string())?;
+    let mut seen = std::collections::HashSet::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let modified = match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            _ => continue,
+        };
+        let project_id = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+
+        let count = match cache.get(&path) {
+            Some((cached_modified, count)) if *cached_modified == modified => *count,
+            _ => {
+                let count = count_project_sessions(&path);
+                cache.insert(path.clone(), (modified, count));
+                count
+            }
+        };
+        seen.insert(path);
+        counts.insert(project_id, count);
+    }
+
+    // Forget projects that have been deleted
+    cache.retain(|path, _| seen.contains(path));
+    Ok(counts)
+}
+
+/// Most recent session files scanned per project by `list_historical_models`
+const MAX_MODEL_SCAN_FILES_PER_PROJECT: usize = 500;
+
+/// A model seen in session history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricalModel {
+    pub model: String,
+    /// Number of sessions with at least one response from this model
+    pub session_count: usize,
+    /// Timestamp of the latest response from this model
+    pub last_seen: Option<String>,
+}
+
+/// Models used in one project: model -> (session count, last seen)
+type ProjectModelSummary = std::collections::HashMap<String, (usize, Option<String>)>;
+
+/// Modification times of a project directory and its newest session file
+type ProjectScanKey = (SystemTime, SystemTime);
+
+/// Cache of per-project model summaries, keyed by project directory and its modification
+/// time together with the newest session file's (appending to a session doesn't touch the directory)
+#[derive(Default)]
+pub struct HistoricalModelCache(
+    std::sync::Mutex<std::collections::HashMap<PathBuf, (ProjectScanKey, ProjectModelSummary)>>,
+);
+
+/// Collect the models in a project's newest session files
+fn scan_project_models(session_files: &[(PathBuf, SystemTime)]) -> ProjectModelSummary {
+    let mut summary = ProjectModelSummary::new();
+    for (path, _) in session_files.iter().take(MAX_MODEL_SCAN_FILES_PER_PROJECT) {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+        let mut session_models: std::collections::HashMap<String, Option<String>> =
+            std::collections::HashMap::new();
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let entry = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            let model = match entry.pointer("/message/model").and_then(|m| m.as_str()) {
+                // Locally generated messages are tagged "<synthetic>", not a real model
+                Some(model) if !model.is_empty() && !model.starts_with('<') => model.to_string(),
+                _ => continue,
+            };
+            let timestamp = entry.get("timestamp").and_then(|t| t.as_str()).map(String::from);
+            let last_seen = session_models.entry(model).or_insert(None);
+            if timestamp > *last_seen {
+                *last_seen = timestamp;
+            }
+        }
+        for (model, timestamp) in session_models {
+            let (count, last_seen) = summary.entry(model).or_insert((0, None));
+            *count += 1;
+            if timestamp > *last_seen {
+                *last_seen = timestamp;
+            }
+        }
+    }
+    summary
+}
+
+/// Lists every distinct model that produced responses in session history, with how many
+/// sessions used it and when it was last seen, most recently used first. Only the newest
+/// sessions of each project are scanned, and unchanged projects are served from a cache.
+#[tauri::command]
+pub async fn list_historical_models(
+    cache: tauri::State<'_, HistoricalModelCache>,
+) -> Result<Vec<HistoricalModel>, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    let mut cache = cache.0.lock().map_err(|e| e.to_string())?;
+    let mut seen = std::collections::HashSet::new();
+    let mut totals = ProjectModelSummary::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let dir_modified = match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            _ => continue,
+        };
+
+        let mut session_files: Vec<(PathBuf, SystemTime)> = fs::read_dir(&path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+            .filter_map(|e| {
+                let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+                Some((e.path(), modified))
+            })
+            .collect();
+        session_files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        let newest_file = session_files.first().map_or(SystemTime::UNIX_EPOCH, |(_, m)| *m);
+        let key = (dir_modified, newest_file);
+
+        let summary = match cache.get(&path) {
+            Some((cached_key, summary)) if *cached_key == key => summary.clone(),
+            _ => {
+                if session_files.len() > MAX_MODEL_SCAN_FILES_PER_PROJECT {
+                    log::debug!(
+                        "Scanning only the newest {} of {} sessions in {:?} for models",
+                        MAX_MODEL_SCAN_FILES_PER_PROJECT,
+                        session_files.len(),
+                        path
+                    );
+                }
+                let summary = scan_project_models(&session_files);
+                cache.insert(path.clone(), (key, summary.clone()));
+                summary
+            }
+        };
+        seen.insert(path);
+
+        for (model, (count, last_seen)) in summary {
+            let total = totals.entry(model).or_insert((0, None));
+            total.0 += count;
+            if last_seen > total.1 {
+                total.1 = last_seen;
+            }
+        }
+    }
+
+    // Forget projects that have been deleted
+    cache.retain(|path, _| seen.contains(path));
+
+    let mut models: Vec<HistoricalModel> = totals
+        .into_iter()
+        .map(|(model, (session_count, last_seen))| HistoricalModel {
+            model,
+            session_count,
+            last_seen,
+        })
+        .collect();
+    models.sort_by(|a, b| b.last_seen.cmp(&a.last_seen).then_with(|| a.model.cmp(&b.model)));
+    Ok(models)
+}
+
 /// Gets sessions for a specific project
 #[tauri::command]
 pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, String> {
@@ -687,7 +1015,7 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
     }
 
     // Sort sessions by creation time (newest first)
-    sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
 
     log::info!(
         "Found {} resumable sessions (filtered) for project {}",
@@ -697,6 +1025,556 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
     Ok(sessions)
 }
 
+/// Maximum length of the first-message preview returned with session metadata
+const SESSION_PREVIEW_CHARS: usize = 200;
+
+/// Bytes read from the end of a session file when looking for the latest model
+const SESSION_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Counts messages without parsing every line and finds the latest model by
+/// scanning only the tail of the file.
+fn read_session_file_meta(path: &PathBuf) -> SessionFileMeta {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let (first_message, message_timestamp) = extract_first_user_message(path);
+
+    let message_count = fs::File::open(path)
+        .map(|file| {
+            BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter(|line| {
+                    line.contains("\"type\":\"user\"") || line.contains("\"type\":\"assistant\"")
+                })
+                .count()
+        })
+        .unwrap_or(0);
+
+    let model = fs::File::open(path).ok().and_then(|mut file| {
+        let len = file.metadata().ok()?.len();
+        let start = len.saturating_sub(SESSION_TAIL_BYTES);
+        file.seek(SeekFrom::Start(start)).ok()?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail).ok()?;
+        let tail = String::from_utf8_lossy(&tail);
+
+        // The first line may be cut in half when we started mid-file; parsing simply fails for it
+        tail.lines().rev().find_map(|line| {
+            serde_json::from_str::<serde_json::Value>(line)
+                .ok()?
+                .get("message")?
+                .get("model")?
+                .as_str()
+                .filter(|m| !m.is_empty() && *m != "<synthetic>")
+                .map(|m| m.to_string())
+        })
+    });
+
+    SessionFileMeta {
+        first_message: first_message.map(|m| {
+            if m.chars().count() > SESSION_PREVIEW_CHARS {
+                format!("{}...", m.chars().take(SESSION_PREVIEW_CHARS).collect::<String>())
+            } else {
+                m
+            }
+        }),
+        message_timestamp,
+        message_count,
+        model,
+    }
+}
+
+/// Resolves a project path (or project directory id) to its ~/.claude/projects directory
+pub(crate) fn find_project_dir(claude_dir: &PathBuf, project_path: &str) -> Option<PathBuf> {
+    let projects_dir = claude_dir.join("projects");
+
+    let direct = projects_dir.join(project_path);
+    if !project_path.contains('/') && direct.is_dir() {
+        return Some(direct);
+    }
+
+    let encoded = projects_dir.join(project_path.replace('/', "-"));
+    if encoded.is_dir() {
+        return Some(encoded);
+    }
+
+    fs::read_dir(&projects_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| dir.is_dir())
+        .find(|dir| {
+            get_project_path_from_sessions(dir)
+                .map(|path| path == project_path)
+                .unwrap_or(false)
+        })
+}
+
+/// Gets sessions for a project along with preview, message count and model
+#[tauri::command]
+pub async fn get_project_sessions_with_meta(
+    cache: tauri::State<'_, SessionMetaCache>,
+    project_path: String,
+) -> Result<Vec<SessionWithMeta>, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let project_dir = find_project_dir(&claude_dir, &project_path)
+        .ok_or_else(|| format!("Project directory not found: {}", project_path))?;
+    let project_id = project_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let todos_dir = claude_dir.join("todos");
+
+    let resolved_path = get_project_path_from_sessions(&project_dir)
+        .unwrap_or_else(|_| decode_project_path(&project_id));
+
+    let entries = fs::read_dir(&project_dir)
+        .map_err(|e| format!("Failed to read project directory: {}", e))?;
+
+    let mut sessions = Vec::new();
+    let mut seen_paths = std::collections::HashSet::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let session_id = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let to_secs = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        };
+        let created_at = to_secs(metadata.created().unwrap_or(modified));
+
+        let cached = cache
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(&path)
+            .filter(|(mtime, _)| *mtime == modified)
+            .map(|(_, meta)| meta.clone());
+        let meta = match cached {
+            Some(meta) => meta,
+            None => {
+                let meta = read_session_file_meta(&path);
+                cache
+                    .0
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .insert(path.clone(), (modified, meta.clone()));
+                meta
+            }
+        };
+        seen_paths.insert(path.clone());
+
+        // Keep parity with get_project_sessions: sessions without a user message are unresumable
+        if meta.first_message.is_none() {
+            continue;
+        }
+
+        let todo_path = todos_dir.join(format!("{}.json", session_id));
+        let todo_data = fs::read_to_string(&todo_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+
+        sessions.push(SessionWithMeta {
+            session: Session {
+                id: session_id,
+                project_id: project_id.clone(),
+                project_path: resolved_path.clone(),
+                todo_data,
+                created_at,
+                first_message: meta.first_message,
+                message_timestamp: meta.message_timestamp,
+            },
+            message_count: meta.message_count,
+            model: meta.model,
+            last_modified: to_secs(modified),
+        });
+    }
+
+    // Drop cache entries for files of this project that no longer exist
+    if let Ok(mut cache) = cache.0.lock() {
+        cache.retain(|path, _| path.parent() != Some(project_dir.as_path()) || seen_paths.contains(path));
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_modified));
+    Ok(sessions)
+}
+
+/// app_settings key prefix for the last-used additional directories of a project
+const ADDITIONAL_DIRS_SETTING_PREFIX: &str = "additional_dirs:";
+
+/// Resolve the additional directories for a session or agent run.
+/// When `requested` is provided the directories are validated and remembered
+/// for the project; otherwise the last-used directories are restored.
+pub(crate) fn resolve_additional_directories(
+    conn: &rusqlite::Connection,
+    project_path: &str,
+    requested: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let key = format!("{}{}", ADDITIONAL_DIRS_SETTING_PREFIX, project_path);
+
+    match requested {
+        Some(dirs) => {
+            let mut validated = Vec::new();
+            for dir in dirs.iter().map(|d| d.trim()).filter(|d| !d.is_empty()) {
+                if !std::path::Path::new(dir).is_dir() {
+                    return Err(format!("Additional directory does not exist: {}", dir));
+                }
+                if !validated.iter().any(|d: &String| d == dir) {
+                    validated.push(dir.to_string());
+                }
+            }
+
+            let value = serde_json::to_string(&validated).map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+                rusqlite::params![key, value],
+            )
+            .map_err(|e| format!("Failed to save additional directories: {}", e))?;
+
+            Ok(validated)
+        }
+        None => {
+            let stored: Vec<String> = conn
+                .query_row(
+                    "SELECT value FROM app_settings WHERE key = ?1",
+                    rusqlite::params![key],
+                    |row| row.get::<_, String>(0),
+                )
+                .ok()
+                .and_then(|value| serde_json::from_str(&value).ok())
+                .unwrap_or_default();
+
+            // Silently drop directories that have since been removed
+            Ok(stored
+                .into_iter()
+                .filter(|dir| std::path::Path::new(dir).is_dir())
+                .collect())
+        }
+    }
+}
+
+/// Build `--add-dir` arguments for the Claude binary
+pub(crate) fn add_dir_args(dirs: &[String]) -> Vec<String> {
+    dirs.iter()
+        .flat_map(|dir| ["--add-dir".to_string(), dir.clone()])
+        .collect()
+}
+
+/// Resolve additional directories using the app's database
+fn resolve_session_directories(
+    app: &AppHandle,
+    project_path: &str,
+    requested: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let db = app.state::<crate::commands::agents::AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    resolve_additional_directories(&conn, project_path, requested)
+}
+
+/// Gets the last-used additional directories for a project
+#[tauri::command]
+pub async fn get_project_additional_directories(
+    app: AppHandle,
+    project_path: String,
+) -> Result<Vec<String>, String> {
+    resolve_session_directories(&app, &project_path, None)
+}
+
+/// A project directory under ~/.claude/projects whose project no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleProject {
+    /// Directory name under ~/.claude/projects
+    pub encoded_name: String,
+    /// The project path the directory refers to
+    pub decoded_path: String,
+    /// Total size of the directory in bytes
+    pub size_bytes: u64,
+    /// Number of session JSONL files in the directory
+    pub session_count: usize,
+    /// Why the directory is considered stale: "missing" or "empty"
+    pub reason: String,
+}
+
+/// Why a project directory is stale, with its decoded path and session count;
+/// None when the project still exists and has sessions
+fn project_dir_staleness(dir: &std::path::Path, encoded_name: &str) -> Option<(String, usize, &'static str)> {
+    let session_count = fs::read_dir(dir)
+        .map(|files| {
+            files
+                .flatten()
+                .filter(|f| f.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+                .count()
+        })
+        .unwrap_or(0);
+
+    let decoded_path = get_project_path_from_sessions(&dir.to_path_buf())
+        .unwrap_or_else(|_| decode_project_path(encoded_name));
+
+    let reason = if session_count == 0 {
+        "empty"
+    } else if !std::path::Path::new(&decoded_path).exists() {
+        "missing"
+    } else {
+        return None;
+    };
+    Some((decoded_path, session_count, reason))
+}
+
+/// Lists project directories that are empty or whose project path no longer exists
+#[tauri::command]
+pub async fn list_stale_project_dirs() -> Result<Vec<StaleProject>, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    let entries = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    let mut stale = Vec::new();
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let encoded_name = entry.file_name().to_string_lossy().to_string();
+
+        let (decoded_path, session_count, reason) = match project_dir_staleness(&dir, &encoded_name) {
+            Some(staleness) => staleness,
+            None => continue,
+        };
+
+        let size_bytes = walkdir::WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum();
+
+        stale.push(StaleProject {
+            encoded_name,
+            decoded_path,
+            size_bytes,
+            session_count,
+            reason: reason.to_string(),
+        });
+    }
+
+    stale.sort_by_key(|s| std::cmp::Reverse(s.size_bytes));
+    Ok(stale)
+}
+
+/// A project directory moved out of ~/.claude/projects by `prune_project_dir`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrunedProject {
+    pub encoded_name: String,
+    pub decoded_path: String,
+    /// Why the directory was stale: "missing" or "empty"
+    pub reason: String,
+    /// Where the directory now lives under ~/.claude/.trash
+    pub trash_path: String,
+}
+
+/// Moves a stale project directory from ~/.claude/projects to ~/.claude/.trash so it
+/// can be recovered. Refuses directories whose project still exists and has sessions,
+/// and never deletes anything: if the move fails the directory is left in place.
+#[tauri::command]
+pub async fn prune_project_dir(encoded_name: String) -> Result<PrunedProject, String> {
+    if encoded_name.is_empty()
+        || encoded_name.contains('/')
+        || encoded_name.contains('\\')
+        || encoded_name == "."
+        || encoded_name == ".."
+    {
+        return Err(format!("Invalid project directory name: {}", encoded_name));
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let project_dir = claude_dir.join("projects").join(&encoded_name);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {}", encoded_name));
+    }
+
+    let (decoded_path, _, reason) = match project_dir_staleness(&project_dir, &encoded_name) {
+        Some(staleness) => staleness,
+        None => {
+            return Err(format!(
+                "Project directory {} is not stale: {} exists and has sessions",
+                encoded_name,
+                decode_project_path(&encoded_name)
+            ))
+        }
+    };
+
+    let trash_dir = claude_dir.join(".trash");
+    let target = trash_dir.join(format!(
+        "{}-{}",
+        encoded_name,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+
+    fs::create_dir_all(&trash_dir)
+        .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+    fs::rename(&project_dir, &target)
+        .map_err(|e| format!("Failed to move project directory to trash: {}", e))?;
+    log::info!("Moved stale ({}) project directory {:?} to {:?}", reason, project_dir, target);
+
+    Ok(PrunedProject {
+        encoded_name,
+        decoded_path,
+        reason: reason.to_string(),
+        trash_path: target.to_string_lossy().to_string(),
+    })
+}
+
+/// A session whose JSONL file exceeds a size threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeSession {
+    pub session_id: String,
+    pub project_id: String,
+    pub project_path: String,
+    pub file_path: String,
+    pub size_bytes: u64,
+}
+
+/// Result of truncating a session JSONL file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TruncateSessionResult {
+    pub backup_path: String,
+    pub lines_before: usize,
+    pub lines_after: usize,
+}
+
+/// Finds session JSONL files larger than `threshold_bytes`, largest first
+#[tauri::command]
+pub async fn get_large_sessions(threshold_bytes: u64) -> Result<Vec<LargeSession>, String> {
+    let projects_dir = get_claude_dir().map_err(|e| e.to_string())?.join("projects");
+    let projects = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    let mut sessions = Vec::new();
+    for project in projects.flatten() {
+        let project_dir = project.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let project_id = project.file_name().to_string_lossy().to_string();
+        let mut project_path: Option<String> = None;
+
+        for entry in fs::read_dir(&project_dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if size_bytes < threshold_bytes {
+                continue;
+            }
+
+            let project_path = project_path
+                .get_or_insert_with(|| {
+                    get_project_path_from_sessions(&project_dir)
+                        .unwrap_or_else(|_| decode_project_path(&project_id))
+                })
+                .clone();
+
+            sessions.push(LargeSession {
+                session_id: path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                project_id: project_id.clone(),
+                project_path,
+                file_path: path.to_string_lossy().to_string(),
+                size_bytes,
+            });
+        }
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.size_bytes));
+    Ok(sessions)
+}
+
+/// Rewrites a session JSONL keeping only the last `keep_last_n_messages` lines.
+/// The original file is backed up next to it before being replaced.
+#[tauri::command]
+pub async fn truncate_session_jsonl(
+    session_id: String,
+    keep_last_n_messages: usize,
+) -> Result<TruncateSessionResult, String> {
+    use std::io::Write;
+
+    if keep_last_n_messages == 0 {
+        return Err("keep_last_n_messages must be greater than zero".to_string());
+    }
+
+    let session_path = find_session_file(&session_id)?;
+
+    // Stream the file keeping only the tail in memory
+    let file = fs::File::open(&session_path)
+        .map_err(|e| format!("Failed to open session file: {}", e))?;
+    let mut tail: std::collections::VecDeque<String> =
+        std::collections::VecDeque::with_capacity(keep_last_n_messages);
+    let mut lines_before = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read session file: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        lines_before += 1;
+        if tail.len() == keep_last_n_messages {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+
+    let backup_path = session_path.with_extension(format!(
+        "jsonl.bak.{}",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    fs::copy(&session_path, &backup_path)
+        .map_err(|e| format!("Failed to back up session file: {}", e))?;
+
+    // Write to a temporary file first so a failure never leaves a half-written session
+    let tmp_path = session_path.with_extension("jsonl.tmp");
+    {
+        let mut tmp = fs::File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temporary file: {}", e))?;
+        for line in &tail {
+            writeln!(tmp, "{}", line).map_err(|e| format!("Failed to write session file: {}", e))?;
+        }
+        tmp.sync_all().map_err(|e| e.to_string())?;
+    }
+    fs::rename(&tmp_path, &session_path)
+        .map_err(|e| format!("Failed to replace session file: {}", e))?;
+
+    log::info!(
+        "Truncated session {} from {} to {} lines (backup: {:?})",
+        session_id,
+        lines_before,
+        tail.len(),
+        backup_path
+    );
+
+    Ok(TruncateSessionResult {
+        backup_path: backup_path.to_string_lossy().to_string(),
+        lines_before,
+        lines_after: tail.len(),
+    })
+}
+
 /// Reads the Claude settings file
 #[tauri::command]
 pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
@@ -723,9 +1601,23 @@ pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
 
 /// Opens a new Claude Code session by executing the claude command
 #[tauri::command]
-pub async fn open_new_session(app: AppHandle, path: Option<String>) -> Result<String, String> {
+pub async fn open_new_session(
+    app: AppHandle,
+    path: Option<String>,
+    additional_directories: Option<Vec<String>>,
+) -> Result<String, String> {
+    let path = path.map(|p| canonical_project_path(&p)).transpose()?;
     log::info!("Opening new Claude Code session at path: {:?}", path);
 
+    let additional_directories = match path.as_deref() {
+        Some(project_path) => resolve_session_directories(&app, project_path, additional_directories)?,
+        None => additional_directories.unwrap_or_default(),
+    };
+    let add_dir = add_dir_args(&additional_directories);
+    if !add_dir.is_empty() {
+        log::info!("Additional directories for new session: {:?}", additional_directories);
+    }
+
     #[cfg(not(debug_assertions))]
     let _claude_path = find_claude_binary(&app)?;
 
@@ -756,6 +1648,7 @@ pub async fn open_new_session(app: AppHandle, path: Option<String>) -> Result<St
         if let Some(project_path) = path {
             cmd.current_dir(&project_path);
         }
+        cmd.args(&add_dir);
 
         // Execute the command
         match cmd.spawn() {
@@ -787,6 +1680,20 @@ pub async fn get_system_prompt() -> Result<String, String> {
     fs::read_to_string(&claude_md_path).map_err(|e| format!("Failed to read CLAUDE.md: {}", e))
 }
 
+/// Reports the shell Claude CLI will run under, with a Git Bash recommendation on Windows
+/// when no POSIX-compatible shell is available
+#[tauri::command]
+pub async fn detect_shell() -> Result<crate::claude_binary::ShellInfo, String> {
+    let info = crate::claude_binary::detect_shell();
+    log::info!(
+        "Detected shell on {}: {:?} (posix: {})",
+        info.platform,
+        info.shell_path,
+        info.is_posix
+    );
+    Ok(info)
+}
+
 /// Checks if Claude Code is installed and gets its version
 #[tauri::command]
 pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus, String> {
@@ -938,34 +1845,276 @@ pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus,
                     None
                 };
 
-                let full_output = if stderr.is_empty() {
-                    stdout.clone()
-                } else {
-                    format!("{}\n{}", stdout, stderr)
-                };
+                let full_output = if stderr.is_empty() {
+                    stdout.clone()
+                } else {
+                    format!("{}\n{}", stdout, stderr)
+                };
+
+                // Check if the output matches the expected format
+                // Expected format: "1.0.17 (Claude Code)" or similar
+                let is_valid = stdout.contains("(Claude Code)") || stdout.contains("Claude Code");
+
+                Ok(ClaudeVersionStatus {
+                    is_installed: is_valid && output.status.success(),
+                    version,
+                    output: full_output.trim().to_string(),
+                })
+            }
+            Err(e) => {
+                log::error!("Failed to run claude command: {}", e);
+                Ok(ClaudeVersionStatus {
+                    is_installed: false,
+                    version: None,
+                    output: format!("Command not found: {}", e),
+                })
+            }
+        }
+    }
+}
+
+/// Environment variables that make Claude CLI authenticate without an interactive login
+const AUTH_ENV_VARS: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_AUTH_TOKEN",
+    "CLAUDE_CODE_OAUTH_TOKEN",
+    "CLAUDE_CODE_USE_BEDROCK",
+    "CLAUDE_CODE_USE_VERTEX",
+];
+
+/// Whether an interactive (OAuth) login is stored. Linux and Windows keep it in
+/// `~/.claude/.credentials.json`; macOS keeps it in the login keychain.
+/// Returns (found, subscription type, expiry in ms since epoch).
+fn stored_oauth_login(home: &std::path::Path) -> (bool, Option<String>, Option<i64>) {
+    let credentials = fs::read_to_string(home.join(".claude").join(".credentials.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    if let Some(oauth) = credentials.as_ref().and_then(|c| c.get("claudeAiOauth")) {
+        let has_token = oauth
+            .get("accessToken")
+            .and_then(|t| t.as_str())
+            .is_some_and(|t| !t.is_empty());
+        return (
+            has_token,
+            oauth.get("subscriptionType").and_then(|s| s.as_str()).map(String::from),
+            oauth.get("expiresAt").and_then(|e| e.as_i64()),
+        );
+    }
+
+    // Only checks that the item exists; the secret itself is never read
+    #[cfg(target_os = "macos")]
+    {
+        let found = std::process::Command::new("security")
+            .args(["find-generic-password", "-s", "Claude Code-credentials"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if found {
+            return (true, None, None);
+        }
+    }
+
+    (false, None, None)
+}
+
+/// Checks whether Claude CLI is authenticated, without running a session.
+/// Both the bundled sidecar and a system binary read the same `~/.claude` credentials,
+/// so this inspects those plus the API-key variables from the environment and settings.json.
+#[tauri::command]
+pub async fn check_claude_auth(app: AppHandle) -> Result<AuthStatus, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+
+    let (binary_path, uses_sidecar) = match find_claude_binary(&app) {
+        Ok(path) => {
+            let sidecar = path == "claude-code";
+            (Some(path), sidecar)
+        }
+        Err(e) => {
+            log::warn!("No Claude binary found while checking auth: {}", e);
+            (None, false)
+        }
+    };
+
+    // API keys can come from the process environment or the `env` block Claude CLI applies
+    let settings = fs::read_to_string(home.join(".claude").join("settings.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    let settings_env = settings.as_ref().and_then(|s| s.get("env"));
+    let mut api_key_env_vars = Vec::new();
+    for var in AUTH_ENV_VARS {
+        let from_env = std::env::var(var).is_ok_and(|v| !v.trim().is_empty());
+        let from_settings = settings_env
+            .and_then(|env| env.get(*var))
+            .and_then(|v| v.as_str())
+            .is_some_and(|v| !v.trim().is_empty());
+        if from_env || from_settings {
+            api_key_env_vars.push(var.to_string());
+        }
+    }
+    let api_key_helper = settings
+        .as_ref()
+        .and_then(|s| s.get("apiKeyHelper"))
+        .and_then(|h| h.as_str())
+        .is_some_and(|h| !h.trim().is_empty());
+
+    let global_config = fs::read_to_string(home.join(".claude.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    let account = global_config.as_ref().and_then(|c| c.get("oauthAccount"));
+    let account_email = account
+        .and_then(|a| a.get("emailAddress"))
+        .and_then(|e| e.as_str())
+        .map(String::from);
+    let organization = account
+        .and_then(|a| a.get("organizationName"))
+        .and_then(|o| o.as_str())
+        .map(String::from);
+    let stored_api_key = global_config
+        .as_ref()
+        .and_then(|c| c.get("primaryApiKey"))
+        .and_then(|k| k.as_str())
+        .is_some_and(|k| !k.is_empty());
+
+    let (oauth_login, subscription_type, expires_at) = stored_oauth_login(&home);
+    let oauth_expired = expires_at.is_some_and(|expires| expires < chrono::Utc::now().timestamp_millis());
+
+    let (method, logged_in) = if !api_key_env_vars.is_empty() {
+        ("environment", true)
+    } else if api_key_helper {
+        ("api_key_helper", true)
+    } else if oauth_login {
+        // Expired access tokens are refreshed by the CLI, so a stored login still counts
+        ("oauth", true)
+    } else if stored_api_key {
+        ("api_key", true)
+    } else {
+        ("none", false)
+    };
+
+    let message = match method {
+        "environment" => format!("Using credentials from {}", api_key_env_vars.join(", ")),
+        "api_key_helper" => "Using apiKeyHelper from ~/.claude/settings.json".to_string(),
+        "oauth" => match &account_email {
+            Some(email) => format!("Logged in as {}", email),
+            None => "Logged in with a Claude account".to_string(),
+        },
+        "api_key" => "Using the API key stored by `claude login`".to_string(),
+        _ => "Not logged in. Run `claude` in a terminal and follow the login prompt, or set ANTHROPIC_API_KEY".to_string(),
+    };
+    log::info!("Claude auth check: {} (method: {})", message, method);
+
+    Ok(AuthStatus {
+        logged_in,
+        method: method.to_string(),
+        account_email,
+        organization,
+        subscription_type,
+        oauth_expired,
+        api_key_env_vars,
+        binary_path,
+        uses_sidecar,
+        message,
+    })
+}
+
+/// First Claude CLI release whose CHANGELOG.md lists each settings key.
+/// Keys under `env` are written as `env.NAME`. Keys that predate the changelog aren't
+/// listed and are treated as supported by every version.
+const SETTINGS_KEY_MIN_VERSIONS: &[(&str, &str)] = &[
+    ("hooks", "1.0.38"),
+    ("statusLine", "1.0.71"),
+    ("outputStyle", "1.0.81"),
+    ("env.ANTHROPIC_DEFAULT_SONNET_MODEL", "1.0.88"),
+    ("env.ANTHROPIC_DEFAULT_OPUS_MODEL", "1.0.88"),
+    ("env.ANTHROPIC_DEFAULT_HAIKU_MODEL", "1.0.88"),
+];
+
+/// First Claude CLI release whose CHANGELOG.md lists each command-line flag
+const CLI_FLAG_MIN_VERSIONS: &[(&str, &str)] = &[
+    ("--add-dir", "1.0.18"),
+];
+
+/// Version of the Claude CLI the app would run. Both probes are cached (per binary
+/// path and mtime, or once per run for the sidecar), so this is cheap after the first call.
+async fn installed_cli_version(app: &AppHandle) -> Option<String> {
+    let claude_path = find_claude_binary(app).ok()?;
+    if claude_path == "claude-code" {
+        crate::commands::agents::bundled_sidecar_version(app).await
+    } else {
+        crate::claude_binary::get_claude_version_async(&claude_path).await
+    }
+}
+
+/// Settings keys and CLI flags supported by the installed Claude CLI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsCapabilities {
+    /// Detected CLI version; when unknown every known key is reported as supported
+    pub version: Option<String>,
+    pub supported_keys: Vec<String>,
+    pub unsupported_keys: Vec<String>,
+    pub supported_flags: Vec<String>,
+    pub unsupported_flags: Vec<String>,
+}
+
+/// Splits a feature table into (supported, unsupported) for a CLI version
+fn partition_by_version(table: &[(&str, &str)], version: Option<&str>) -> (Vec<String>, Vec<String>) {
+    let (supported, unsupported): (Vec<_>, Vec<_>) = table.iter().partition(|(_, min)| match version {
+        Some(version) => crate::claude_binary::compare_versions(version, min) != std::cmp::Ordering::Less,
+        None => true,
+    });
+    (
+        supported.into_iter().map(|(key, _)| key.to_string()).collect(),
+        unsupported.into_iter().map(|(key, _)| key.to_string()).collect(),
+    )
+}
 
-                // Check if the output matches the expected format
-                // Expected format: "1.0.17 (Claude Code)" or similar
-                let is_valid = stdout.contains("(Claude Code)") || stdout.contains("Claude Code");
+/// Returns the keys in `settings` that the given CLI version will not honor
+pub(crate) fn unsupported_settings_keys(settings: &serde_json::Value, version: &str) -> Vec<String> {
+    let (_, unsupported) = partition_by_version(SETTINGS_KEY_MIN_VERSIONS, Some(version));
+    let mut present = Vec::new();
+    if let Some(obj) = settings.as_object() {
+        present.extend(obj.keys().cloned());
+        if let Some(env) = obj.get("env").and_then(|e| e.as_object()) {
+            present.extend(env.keys().map(|key| format!("env.{}", key)));
+        }
+    }
+    present.retain(|key| unsupported.contains(key));
+    present
+}
 
-                Ok(ClaudeVersionStatus {
-                    is_installed: is_valid && output.status.success(),
-                    version,
-                    output: full_output.trim().to_string(),
-                })
-            }
-            Err(e) => {
-                log::error!("Failed to run claude command: {}", e);
-                Ok(ClaudeVersionStatus {
-                    is_installed: false,
-                    version: None,
-                    output: format!("Command not found: {}", e),
-                })
-            }
+/// Logs a warning for settings keys the installed CLI version won't honor
+async fn warn_unsupported_settings(app: &AppHandle, settings: &serde_json::Value) {
+    if let Some(version) = installed_cli_version(app).await {
+        let unsupported = unsupported_settings_keys(settings, &version);
+        if !unsupported.is_empty() {
+            log::warn!(
+                "Claude CLI {} does not support these settings keys, they will be ignored: {}",
+                version,
+                unsupported.join(", ")
+            );
         }
     }
 }
 
+/// Detects the installed Claude CLI version and reports which settings keys and flags it supports
+#[tauri::command]
+pub async fn get_claude_settings_capabilities(app: AppHandle) -> Result<SettingsCapabilities, String> {
+    find_claude_binary(&app)?;
+    let version = installed_cli_version(&app).await;
+
+    let (supported_keys, unsupported_keys) = partition_by_version(SETTINGS_KEY_MIN_VERSIONS, version.as_deref());
+    let (supported_flags, unsupported_flags) = partition_by_version(CLI_FLAG_MIN_VERSIONS, version.as_deref());
+
+    Ok(SettingsCapabilities {
+        version,
+        supported_keys,
+        unsupported_keys,
+        supported_flags,
+        unsupported_flags,
+    })
+}
+
 /// Saves the CLAUDE.md system prompt file
 #[tauri::command]
 pub async fn save_system_prompt(content: String) -> Result<String, String> {
@@ -981,8 +2130,9 @@ pub async fn save_system_prompt(content: String) -> Result<String, String> {
 
 /// Saves the Claude settings file
 #[tauri::command]
-pub async fn save_claude_settings(settings: serde_json::Value) -> Result<String, String> {
+pub async fn save_claude_settings(app: AppHandle, settings: serde_json::Value) -> Result<String, String> {
     log::info!("Saving Claude settings");
+    warn_unsupported_settings(&app, &settings).await;
 
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let settings_path = claude_dir.join("settings.json");
@@ -993,16 +2143,58 @@ pub async fn save_claude_settings(settings: serde_json::Value) -> Result<String,
 
     fs::write(&settings_path, json_string)
         .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    crate::commands::settings_monitor::record_app_settings_write(&app, &settings_path).await;
 
     Ok("Settings saved successfully".to_string())
 }
 
-/// Updates Claude settings.json with environment variables from active group
+/// One environment variable written to settings.json by a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrittenEnvVar {
+    pub key: String,
+    /// Secrets (tokens, keys, passwords) are masked
+    pub value: String,
+}
+
+/// Outcome of applying the enabled environment groups to settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshResult {
+    pub settings_path: String,
+    pub written: Vec<WrittenEnvVar>,
+    /// The file re-read from disk contains every written value
+    pub verified: bool,
+    /// Keys whose value on disk differs from what was written
+    pub mismatched_keys: Vec<String>,
+    pub message: String,
+}
+
+/// Re-read settings.json and list the written keys whose value on disk differs
+fn verify_settings_env(
+    settings_path: &std::path::Path,
+    written: &std::collections::BTreeMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(settings_path)
+        .map_err(|e| format!("Failed to re-read settings file: {}", e))?;
+    let settings: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Settings file is not valid JSON after writing: {}", e))?;
+    let env = settings.get("env");
+
+    Ok(written
+        .iter()
+        .filter(|(key, value)| {
+            env.and_then(|env| env.get(key.as_str())).and_then(|v| v.as_str()) != Some(value.as_str())
+        })
+        .map(|(key, _)| key.clone())
+        .collect())
+}
+
+/// Updates Claude settings.json with environment variables from active group,
+/// then re-reads the file to confirm every value took effect
 #[tauri::command]
 pub async fn update_claude_settings_with_env_group(
     app: AppHandle,
     group_id: Option<i64>,
-) -> Result<String, String> {
+) -> Result<RefreshResult, String> {
     use crate::commands::agents::{AgentDb, get_enabled_environment_variables};
     
     log::info!("Updating Claude settings.json with environment group: {:?}", group_id);
@@ -1021,76 +2213,6 @@ pub async fn update_claude_settings_with_env_group(
         }
     };
 
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-    let settings_path = claude_dir.join("settings.json");
-
-    // Read current settings or create empty object
-    let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path)
-            .map_err(|e| format!("Failed to read settings file: {}", e))?;
-        
-        // Handle empty or invalid JSON files
-        if content.trim().is_empty() {
-            log::info!("Settings file is empty, creating new JSON object");
-            serde_json::json!({})
-        } else {
-            match serde_json::from_str(&content) {
-                Ok(parsed) => parsed,
-                Err(e) => {
-                    log::warn!("Failed to parse settings JSON ({}), creating new JSON object", e);
-                    serde_json::json!({})
-                }
-            }
-        }
-    } else {
-        log::info!("Settings file does not exist, creating new JSON object");
-        serde_json::json!({})
-    };
-
-    // Ensure settings is an object
-    if !settings.is_object() {
-        settings = serde_json::json!({});
-    }
-
-    // Intelligent merge of environment variables
-    let settings_obj = settings.as_object_mut().unwrap();
-    
-    // Preserve existing env object and intelligently merge
-    let existing_env = settings_obj.get("env")
-        .and_then(|e| e.as_object())
-        .cloned()
-        .unwrap_or_default();
-
-    // Create new env object with our managed variables
-    let mut new_env_obj = serde_json::Map::new();
-
-    // First, preserve non-managed external variables
-    for (key, value) in &existing_env {
-        if !is_managed_by_termiclaude(key, &env_vars) {
-            new_env_obj.insert(key.clone(), value.clone());
-            log::debug!("Preserving external variable: {} = {:?}", key, value);
-        }
-    }
-
-    // 写入运行时需要的核心参数，支持多种API提供商
-    let potential_runtime_keys = [
-        // Anthropic API
-        "ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_BASE_URL", "ANTHROPIC_API_KEY",
-        // OpenAI API  
-        "OPENAI_API_KEY", "OPENAI_BASE_URL",
-        // Generic API
-        "API_BASE_URL", "API_KEY",
-        // Chinese AI providers
-        "ZHIPU_API_KEY", "QWEN_API_KEY", "GLM_API_KEY",
-    ];
-    
-    for key in &potential_runtime_keys {
-        if let Some(value) = env_vars.get(*key) {
-            new_env_obj.insert(key.to_string(), serde_json::Value::String(value.clone()));
-            log::debug!("Setting runtime variable: {} = {}", key, value);
-        }
-    }
-
     // 🔧 FIX: 确保选择的模型来自当前启用的环境变量组
     let current_selected_model = {
         // 首先尝试从当前环境变量中找到首选模型
@@ -1148,10 +2270,7 @@ pub async fn update_claude_settings_with_env_group(
         }
     };
     
-    if let Some(model) = current_selected_model {
-        new_env_obj.insert("ANTHROPIC_MODEL".to_string(), serde_json::Value::String(model.clone()));
-        log::info!("🎯 Set ANTHROPIC_MODEL to: {}", model);
-        
+    if let Some(model) = &current_selected_model {
         // 🔧 关键修复：确保当前选择的模型也保存到app_settings表中
         // 这样下次启动时就能正确读取到这个模型选择
         if let Err(e) = crate::commands::storage::save_app_setting(
@@ -1165,6 +2284,154 @@ pub async fn update_claude_settings_with_env_group(
         }
     }
 
+    // Keys written last time are removed when their variable is no longer enabled
+    let previously_written: std::collections::BTreeSet<String> =
+        crate::commands::storage::get_app_setting(app.clone(), MANAGED_ENV_KEYS_SETTING.to_string())
+            .await
+            .ok()
+            .flatten()
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or_default();
+
+    let settings_path = get_claude_dir().map_err(|e| e.to_string())?.join("settings.json");
+    let written = write_env_vars_to_settings(
+        &settings_path,
+        &env_vars,
+        current_selected_model.as_deref(),
+        &previously_written,
+    )?;
+    crate::commands::settings_monitor::record_app_settings_write(&app, &settings_path).await;
+
+    let written_keys: Vec<&String> = written.keys().collect();
+    match serde_json::to_string(&written_keys) {
+        Ok(value) => {
+            if let Err(e) = crate::commands::storage::save_app_setting(
+                app.clone(),
+                MANAGED_ENV_KEYS_SETTING.to_string(),
+                value,
+            )
+            .await
+            {
+                log::warn!("Failed to record written settings.json keys: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize written settings.json keys: {}", e),
+    }
+
+    let mismatched_keys = verify_settings_env(&settings_path, &written)?;
+    let verified = mismatched_keys.is_empty();
+    let message = if verified {
+        log::info!("Successfully updated Claude settings.json with core runtime parameters");
+        format!("Claude settings updated with {} variables", written.len())
+    } else {
+        log::warn!("settings.json does not match after writing: {}", mismatched_keys.join(", "));
+        format!("settings.json does not match for: {}", mismatched_keys.join(", "))
+    };
+
+    let written = written
+        .into_iter()
+        .map(|(key, value)| {
+            let value = if crate::commands::settings_monitor::is_secret_key(&key) {
+                crate::commands::agents::mask_secret(&value)
+            } else {
+                value
+            };
+            WrittenEnvVar { key, value }
+        })
+        .collect();
+
+    Ok(RefreshResult {
+        settings_path: settings_path.to_string_lossy().to_string(),
+        written,
+        verified,
+        mismatched_keys,
+        message,
+    })
+}
+
+/// app_settings key holding the settings.json env keys written by the last refresh
+pub(crate) const MANAGED_ENV_KEYS_SETTING: &str = "settings_managed_env_keys";
+
+/// Model definition variables (MID_*/MNAME_*/MDESC_*) only drive TermiClaude's model picker
+fn is_model_definition_var(key: &str) -> bool {
+    key.starts_with("MID_") || key.starts_with("MNAME_") || key.starts_with("MDESC_")
+}
+
+/// Merges enabled environment variables into the `env` object of a settings.json file.
+/// Every enabled variable is written except model definitions; keys written last time
+/// (`previously_written`) that are no longer enabled are removed, other external variables
+/// are preserved. Returns the variables that were written.
+fn write_env_vars_to_settings(
+    settings_path: &std::path::Path,
+    env_vars: &std::collections::HashMap<String, String>,
+    selected_model: Option<&str>,
+    previously_written: &std::collections::BTreeSet<String>,
+) -> Result<std::collections::BTreeMap<String, String>, String> {
+    // Read current settings or create empty object
+    let mut settings: serde_json::Value = if settings_path.exists() {
+        let content = fs::read_to_string(settings_path)
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+        
+        // Handle empty or invalid JSON files
+        if content.trim().is_empty() {
+            log::info!("Settings file is empty, creating new JSON object");
+            serde_json::json!({})
+        } else {
+            match serde_json::from_str(&content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    log::warn!("Failed to parse settings JSON ({}), creating new JSON object", e);
+                    serde_json::json!({})
+                }
+            }
+        }
+    } else {
+        log::info!("Settings file does not exist, creating new JSON object");
+        serde_json::json!({})
+    };
+
+    // Ensure settings is an object
+    if !settings.is_object() {
+        settings = serde_json::json!({});
+    }
+
+    // Intelligent merge of environment variables
+    let settings_obj = settings.as_object_mut().unwrap();
+    
+    // Preserve existing env object and intelligently merge
+    let existing_env = settings_obj.get("env")
+        .and_then(|e| e.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    // Create new env object with our managed variables
+    let mut new_env_obj = serde_json::Map::new();
+
+    // First, preserve non-managed external variables
+    for (key, value) in &existing_env {
+        if !is_managed_by_termiclaude(key, env_vars) && !previously_written.contains(key) {
+            new_env_obj.insert(key.clone(), value.clone());
+            log::debug!("Preserving external variable: {} = {:?}", key, value);
+        }
+    }
+
+    // 写入所有启用的环境变量（如 ANTHROPIC_DEFAULT_*_MODEL），而不是固定的子集
+    let mut written = std::collections::BTreeMap::new();
+    for (key, value) in env_vars {
+        if is_model_definition_var(key) {
+            continue;
+        }
+        new_env_obj.insert(key.clone(), serde_json::Value::String(value.clone()));
+        written.insert(key.clone(), value.clone());
+        log::debug!("Setting runtime variable: {}", key);
+    }
+
+    if let Some(model) = selected_model {
+        new_env_obj.insert("ANTHROPIC_MODEL".to_string(), serde_json::Value::String(model.to_string()));
+        written.insert("ANTHROPIC_MODEL".to_string(), model.to_string());
+        log::info!("🎯 Set ANTHROPIC_MODEL to: {}", model);
+    }
+
     // Update the settings with the merged env
     settings_obj.insert("env".to_string(), serde_json::Value::Object(new_env_obj));
 
@@ -1172,11 +2439,9 @@ pub async fn update_claude_settings_with_env_group(
     let json_string = serde_json::to_string_pretty(&settings)
         .map_err(|e| format!("Failed to serialize updated settings: {}", e))?;
 
-    fs::write(&settings_path, json_string)
+    fs::write(settings_path, json_string)
         .map_err(|e| format!("Failed to write updated settings file: {}", e))?;
-
-    log::info!("Successfully updated Claude settings.json with core runtime parameters");
-    Ok("Claude settings updated with core runtime parameters".to_string())
+    Ok(written)
 }
 
 /// Check if a variable key is managed by TermiClaude
@@ -1201,7 +2466,7 @@ fn is_managed_by_termiclaude(key: &str, internal_vars: &std::collections::HashMa
 }
 
 /// Find the preferred model from environment variables (prioritize MID_* over direct ANTHROPIC_MODEL)
-fn find_preferred_model(env_vars: &std::collections::HashMap<String, String>) -> Option<String> {
+pub(crate) fn find_preferred_model(env_vars: &std::collections::HashMap<String, String>) -> Option<String> {
     // First check for MID_1, MID_2, MID_3, etc. (custom model configurations)
     for i in 1..=10 {
         let mid_key = format!("MID_{}", i);
@@ -1289,6 +2554,7 @@ pub async fn update_claude_settings_with_model(
     // Update ANTHROPIC_MODEL
     env_obj.insert("ANTHROPIC_MODEL".to_string(), serde_json::Value::String(model_id.clone()));
     log::info!("Updated ANTHROPIC_MODEL in settings.json env: {}", model_id);
+    warn_unsupported_settings(&app, &settings).await;
 
     // Save updated settings
     let json_string = serde_json::to_string_pretty(&settings)
@@ -1296,6 +2562,7 @@ pub async fn update_claude_settings_with_model(
 
     fs::write(&settings_path, json_string)
         .map_err(|e| format!("Failed to write updated settings file: {}", e))?;
+    crate::commands::settings_monitor::record_app_settings_write(&app, &settings_path).await;
 
     log::info!("Successfully updated Claude settings.json with model");
     Ok("Claude settings updated with model".to_string())
@@ -1510,6 +2777,220 @@ pub async fn load_session_history(
     Ok(messages)
 }
 
+/// A page of parsed messages from a session JSONL file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPage {
+    pub messages: Vec<serde_json::Value>,
+    pub offset: usize,
+    /// Number of valid messages in the session
+    pub total: usize,
+    pub has_more: bool,
+}
+
+/// Loads `limit` messages starting at `offset` from a session.
+/// Only the requested slice is kept in memory; malformed lines are skipped and not counted.
+#[tauri::command]
+pub async fn get_session_messages(
+    session_id: String,
+    offset: usize,
+    limit: usize,
+) -> Result<SessionPage, String> {
+    let session_path = find_session_file(&session_id)?;
+    let file = fs::File::open(&session_path)
+        .map_err(|e| format!("Failed to open session file: {}", e))?;
+
+    let mut messages = Vec::with_capacity(limit.min(1000));
+    let mut total = 0;
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to read line from session {}: {}", session_id, e);
+                continue;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let in_page = total >= offset && total - offset < limit;
+        if in_page {
+            match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(message) => messages.push(message),
+                Err(_) => continue,
+            }
+        } else if serde_json::from_str::<serde::de::IgnoredAny>(&line).is_err() {
+            // Validate without building a value so the total stays consistent
+            continue;
+        }
+        total += 1;
+    }
+
+    Ok(SessionPage {
+        has_more: offset + messages.len() < total,
+        messages,
+        offset,
+        total,
+    })
+}
+
+/// SHA-256 of a file, read in chunks so large transcripts never sit in memory
+pub(crate) fn hash_session_file(path: &std::path::Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Returns the SHA-256 (hex) of a session's JSONL transcript
+#[tauri::command]
+pub async fn get_session_hash(session_id: String) -> Result<String, String> {
+    let path = find_session_file(&session_id)?;
+    tokio::task::spawn_blocking(move || hash_session_file(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Checks a session transcript against a previously recorded SHA-256
+#[tauri::command]
+pub async fn verify_session_hash(session_id: String, expected_hash: String) -> Result<bool, String> {
+    let actual = get_session_hash(session_id).await?;
+    Ok(actual.eq_ignore_ascii_case(expected_hash.trim()))
+}
+
+/// Maximum characters of tool output kept per call in `list_session_tool_calls`
+const TOOL_OUTPUT_MAX_CHARS: usize = 2000;
+
+/// Outcome of a tool call, taken from its matching tool_result
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Success,
+    Error,
+    /// No tool_result was found (interrupted or still running)
+    Pending,
+}
+
+/// One tool invocation from a session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+    pub timestamp: Option<String>,
+    pub status: ToolCallStatus,
+    /// Result text, truncated to `TOOL_OUTPUT_MAX_CHARS`
+    pub output: Option<String>,
+    pub output_truncated: bool,
+}
+
+/// Flattens tool_result content, which is either a string or a list of content blocks
+fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => block.get("text").and_then(|t| t.as_str()).map(String::from),
+                Some(other) => Some(format!("[{}]", other)),
+                None => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Lists every tool call in a session in order, paired with its result
+#[tauri::command]
+pub async fn list_session_tool_calls(session_id: String) -> Result<Vec<ToolCall>, String> {
+    let session_path = find_session_file(&session_id)?;
+    let file = fs::File::open(&session_path)
+        .map_err(|e| format!("Failed to open session file: {}", e))?;
+
+    let mut calls: Vec<ToolCall> = Vec::new();
+    let mut index_by_id: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => continue,
+        };
+        let entry = match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        let blocks = match entry
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+        {
+            Some(blocks) => blocks,
+            None => continue,
+        };
+        let timestamp = entry.get("timestamp").and_then(|t| t.as_str()).map(String::from);
+
+        for block in blocks {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("tool_use") => {
+                    let id = block.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                    if !id.is_empty() {
+                        index_by_id.insert(id.clone(), calls.len());
+                    }
+                    calls.push(ToolCall {
+                        id,
+                        name: block.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        input: block.get("input").cloned().unwrap_or(serde_json::Value::Null),
+                        timestamp: timestamp.clone(),
+                        status: ToolCallStatus::Pending,
+                        output: None,
+                        output_truncated: false,
+                    });
+                }
+                Some("tool_result") => {
+                    let index = match block
+                        .get("tool_use_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|id| index_by_id.get(id))
+                    {
+                        Some(index) => *index,
+                        None => continue,
+                    };
+                    let text = tool_result_text(block.get("content").unwrap_or(&serde_json::Value::Null));
+                    let call = &mut calls[index];
+                    call.status = if block.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false) {
+                        ToolCallStatus::Error
+                    } else {
+                        ToolCallStatus::Success
+                    };
+                    call.output_truncated = text.chars().count() > TOOL_OUTPUT_MAX_CHARS;
+                    call.output = Some(if call.output_truncated {
+                        text.chars().take(TOOL_OUTPUT_MAX_CHARS).collect()
+                    } else {
+                        text
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(calls)
+}
+
 /// Saves the JSONL history for a specific session (supports message deletion)
 #[tauri::command]
 pub async fn save_session_history(
@@ -1559,9 +3040,11 @@ pub async fn execute_claude_code(
     project_path: String,
     prompt: String,
     model: String,
+    additional_directories: Option<Vec<String>>,
 ) -> Result<(), String> {
     use crate::commands::agents::{AgentDb, get_enabled_environment_variables};
     use crate::commands::mcp::{mcp_read_project_config, MCPProjectConfig};
+    let project_path = canonical_project_path(&project_path)?;
     log::info!(
         "🆕 Starting new Claude Code session in: {} with model: {}",
         project_path,
@@ -1621,8 +3104,10 @@ pub async fn execute_claude_code(
     log::info!("🎯 Setting ANTHROPIC_MODEL to user selected model: {}", model);
 
     let claude_path = find_claude_binary(&app)?;
+    let additional_directories =
+        resolve_session_directories(&app, &project_path, additional_directories)?;
 
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
@@ -1632,6 +3117,7 @@ pub async fn execute_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(add_dir_args(&additional_directories));
 
     // On macOS, when the stored path is the special sidecar identifier, use sidecar to spawn
     #[cfg(target_os = "macos")]
@@ -1651,6 +3137,7 @@ pub async fn continue_claude_code(
     project_path: String,
     prompt: String,
     model: String,
+    additional_directories: Option<Vec<String>>,
 ) -> Result<(), String> {
     use crate::commands::agents::{AgentDb, get_enabled_environment_variables};
     use crate::commands::mcp::{mcp_read_project_config, MCPProjectConfig};
@@ -1702,8 +3189,10 @@ pub async fn continue_claude_code(
     log::info!("🎯 Setting ANTHROPIC_MODEL to user selected model: {}", model);
 
     let claude_path = find_claude_binary(&app)?;
+    let additional_directories =
+        resolve_session_directories(&app, &project_path, additional_directories)?;
 
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
@@ -1713,6 +3202,7 @@ pub async fn continue_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(add_dir_args(&additional_directories));
 
     // On macOS, when the stored path is the special sidecar identifier, use sidecar to spawn
     #[cfg(target_os = "macos")]
@@ -1732,6 +3222,7 @@ pub async fn resume_claude_code(
     session_id: String,
     prompt: String,
     model: String,
+    additional_directories: Option<Vec<String>>,
 ) -> Result<(), String> {
     use crate::commands::agents::{AgentDb, get_enabled_environment_variables};
     use crate::commands::mcp::{mcp_read_project_config, MCPProjectConfig};
@@ -1793,8 +3284,10 @@ pub async fn resume_claude_code(
     log::info!("🎯 Setting ANTHROPIC_MODEL to user selected model: {}", model);
 
     let claude_path = find_claude_binary(&app)?;
+    let additional_directories =
+        resolve_session_directories(&app, &project_path, additional_directories)?;
 
-    let args = vec![
+    let mut args = vec![
         "--resume".to_string(),
         session_id.clone(),
         "-p".to_string(),
@@ -1806,6 +3299,7 @@ pub async fn resume_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(add_dir_args(&additional_directories));
 
     // On macOS, when the stored path is the special sidecar identifier, use sidecar to spawn
     #[cfg(target_os = "macos")]
@@ -1813,8 +3307,50 @@ pub async fn resume_claude_code(
         return spawn_claude_sidecar(app, args, prompt, model, project_path).await;
     }
 
-    let cmd = create_system_command_with_env(&claude_path, args, &project_path, &env_vars);
-    spawn_claude_process(app, cmd, prompt, model, project_path).await
+    let cmd = create_system_command_with_env(&claude_path, args, &project_path, &env_vars);
+    spawn_claude_process(app, cmd, prompt, model, project_path).await
+}
+
+/// Finds the newest session (by JSONL modification time) in a project directory
+fn latest_session_id(project_dir: &PathBuf) -> Option<String> {
+    fs::read_dir(project_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let session_id = path.file_stem()?.to_str()?.to_string();
+            Some((modified, session_id))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, session_id)| session_id)
+}
+
+/// Resume the most recently active session of a project, optionally with a follow-up task.
+/// Returns the resumed session id; fails if the project has no sessions yet.
+#[tauri::command]
+pub async fn resume_latest_session(
+    app: AppHandle,
+    project_path: String,
+    task: Option<String>,
+    model: Option<String>,
+) -> Result<String, String> {
+    let project_path = canonical_project_path(&project_path)?;
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+
+    let session_id = find_project_dir(&claude_dir, &project_path)
+        .and_then(|dir| latest_session_id(&dir))
+        .ok_or_else(|| format!("No previous sessions found for project: {}", project_path))?;
+    log::info!("Resuming latest session {} for project {}", session_id, project_path);
+
+    let prompt = task
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "continue".to_string());
+    let model = model.unwrap_or_else(|| "sonnet".to_string());
+
+    resume_claude_code(app, project_path, session_id.clone(), prompt, model, None).await?;
+    Ok(session_id)
 }
 
 /// Cancel the currently running Claude Code execution
@@ -1983,6 +3519,18 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     use tokio::io::{AsyncBufReadExt, BufReader};
     use std::sync::Mutex;
 
+    // Record which binary produces this session
+    let binary_path = cmd.as_std().get_program().to_string_lossy().to_string();
+    let binary_version = crate::claude_binary::get_claude_version_async(&binary_path).await;
+    if let Err(e) = crate::commands::agents::record_session_binary_version(
+        &app,
+        &binary_path,
+        binary_version.as_deref(),
+        &project_path,
+    ) {
+        log::warn!("Failed to record Claude binary version: {}", e);
+    }
+
     // Spawn the process
     let mut child = cmd
         .spawn()
@@ -2028,6 +3576,8 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
     let project_path_clone = project_path.clone();
     let prompt_clone = prompt.clone();
     let model_clone = model.clone();
+    let binary_path_clone = binary_path.clone();
+    let binary_version_clone = binary_version.clone();
     let stdout_task = tokio::spawn(async move {
         let mut lines = stdout_reader.lines();
         while let Ok(Some(line)) = lines.next_line().await {
@@ -2055,6 +3605,8 @@ async fn spawn_claude_process(app: AppHandle, mut cmd: Command, prompt: String,
                                 project_path_clone.clone(),
                                 prompt_clone.clone(),
                                 model_clone.clone(),
+                                Some(binary_path_clone.clone()),
+                                binary_version_clone.clone(),
                             ) {
                                 Ok(run_id) => {
                                     log::info!("Registered Claude session with run_id: {}", run_id);
@@ -2241,6 +3793,8 @@ async fn spawn_claude_sidecar(
                                                 project_path_clone.clone(),
                                                 prompt_clone.clone(),
                                                 model_clone.clone(),
+                                                Some("claude-code".to_string()),
+                                                None,
                                             ) {
                                                 Ok(run_id) => {
                                                     log::info!("Registered Claude sidecar session with run_id: {}", run_id);
@@ -2647,6 +4201,58 @@ pub async fn list_checkpoints(
     Ok(manager.list_checkpoints().await)
 }
 
+/// Lists checkpoints from every session, newest first, optionally only those
+/// belonging to `project_path`
+#[tauri::command]
+pub async fn list_all_checkpoints(
+    limit: usize,
+    project_path: Option<String>,
+) -> Result<Vec<crate::checkpoint::CheckpointSummary>, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let storage = crate::checkpoint::storage::CheckpointStorage::new(claude_dir.clone());
+    let timelines = storage
+        .load_all_timelines()
+        .map_err(|e| format!("Failed to read checkpoint timelines: {}", e))?;
+
+    let wanted_project = project_path
+        .as_deref()
+        .map(|path| canonical_project_path(path).unwrap_or_else(|_| path.trim().to_string()));
+
+    // project id -> working directory, resolved once per project
+    let mut project_paths: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+    let mut summaries = Vec::new();
+    for (project_id, timeline) in timelines {
+        let path = project_paths
+            .entry(project_id.clone())
+            .or_insert_with(|| {
+                get_project_path_from_sessions(&claude_dir.join("projects").join(&project_id))
+                    .unwrap_or_else(|_| decode_project_path(&project_id))
+            })
+            .clone();
+        if wanted_project.as_ref().is_some_and(|wanted| *wanted != path) {
+            continue;
+        }
+
+        for checkpoint in timeline.checkpoints() {
+            summaries.push(crate::checkpoint::CheckpointSummary {
+                checkpoint_id: checkpoint.id.clone(),
+                session_id: timeline.session_id.clone(),
+                project_id: project_id.clone(),
+                project_path: path.clone(),
+                name: checkpoint.description.clone(),
+                timestamp: checkpoint.timestamp,
+                file_count: checkpoint.metadata.file_changes,
+                message_index: checkpoint.message_index,
+            });
+        }
+    }
+
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    summaries.truncate(limit);
+    Ok(summaries)
+}
+
 /// Forks a new timeline branch from a checkpoint
 #[tauri::command]
 pub async fn fork_from_checkpoint(
@@ -2719,6 +4325,38 @@ pub async fn get_session_timeline(
     Ok(manager.get_timeline().await)
 }
 
+/// Exports a session's checkpoint timeline (including forks) as Graphviz DOT
+#[tauri::command]
+pub async fn export_timeline_dot(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    session_id: String,
+) -> Result<String, String> {
+    log::info!("Exporting timeline as DOT for session: {}", session_id);
+
+    // Prefer the live timeline when the session is active
+    if let Some(manager) = app.get_manager(&session_id).await {
+        return Ok(manager.get_timeline().await.to_dot());
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let projects = fs::read_dir(claude_dir.join("projects"))
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    for entry in projects.flatten() {
+        let project_id = entry.file_name().to_string_lossy().to_string();
+        let paths = crate::checkpoint::CheckpointPaths::new(&claude_dir, &project_id, &session_id);
+        if paths.timeline_file.exists() {
+            let storage = crate::checkpoint::storage::CheckpointStorage::new(claude_dir.clone());
+            let timeline = storage
+                .load_timeline(&paths.timeline_file)
+                .map_err(|e| format!("Failed to load timeline: {}", e))?;
+            return Ok(timeline.to_dot());
+        }
+    }
+
+    Err(format!("No checkpoint timeline found for session: {}", session_id))
+}
+
 /// Updates checkpoint settings for a session
 #[tauri::command]
 pub async fn update_checkpoint_settings(
@@ -2728,6 +4366,8 @@ pub async fn update_checkpoint_settings(
     project_path: String,
     auto_checkpoint_enabled: bool,
     checkpoint_strategy: String,
+    checkpoint_on_file_write: Option<bool>,
+    auto_checkpoint_every_n_messages: Option<u32>,
 ) -> Result<(), String> {
     use crate::checkpoint::CheckpointStrategy;
 
@@ -2752,7 +4392,12 @@ pub async fn update_checkpoint_settings(
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
     manager
-        .update_settings(auto_checkpoint_enabled, strategy)
+        .update_settings(
+            auto_checkpoint_enabled,
+            strategy,
+            checkpoint_on_file_write,
+            auto_checkpoint_every_n_messages,
+        )
         .await
         .map_err(|e| format!("Failed to update settings: {}", e))
 }
@@ -2863,7 +4508,13 @@ pub async fn track_checkpoint_message(
     manager
         .track_message(message)
         .await
-        .map_err(|e| format!("Failed to track message: {}", e))
+        .map_err(|e| format!("Failed to track message: {}", e))?;
+
+    manager
+        .checkpoint_if_message_threshold()
+        .await
+        .map_err(|e| format!("Failed to create message-count checkpoint: {}", e))?;
+    Ok(())
 }
 
 /// Checks if auto-checkpoint should be triggered
@@ -2935,6 +4586,9 @@ pub async fn get_checkpoint_settings(
     Ok(serde_json::json!({
         "auto_checkpoint_enabled": timeline.auto_checkpoint_enabled,
         "checkpoint_strategy": timeline.checkpoint_strategy,
+        "checkpoint_on_file_write": timeline.checkpoint_on_file_write,
+        "auto_checkpoint_every_n_messages": timeline.auto_checkpoint_every_n_messages,
+        "messages_since_last_checkpoint": manager.messages_since_checkpoint(),
         "total_checkpoints": timeline.total_checkpoints,
         "current_checkpoint_id": timeline.current_checkpoint_id,
     }))
@@ -2952,6 +4606,22 @@ pub async fn clear_checkpoint_manager(
     Ok(())
 }
 
+/// Drops a single session's in-memory checkpoint manager.
+/// Returns whether a manager was loaded for that session.
+#[tauri::command]
+pub async fn clear_checkpoint_manager_for_session(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    session_id: String,
+) -> Result<bool, String> {
+    let removed = app.remove_manager(&session_id).await.is_some();
+    log::info!(
+        "Clearing checkpoint manager for session {}: {}",
+        session_id,
+        if removed { "removed" } else { "not loaded" }
+    );
+    Ok(removed)
+}
+
 /// Gets checkpoint state statistics (for debugging/monitoring)
 #[tauri::command]
 pub async fn get_checkpoint_state_stats(
@@ -2966,7 +4636,142 @@ pub async fn get_checkpoint_state_stats(
     }))
 }
 
-/// Gets files modified in the last N minutes for a session
+/// Directory depth limit for the recently-modified scan
+const RECENT_FILES_MAX_DEPTH: usize = 16;
+/// Maximum number of paths returned by the recently-modified scan
+const RECENT_FILES_MAX_RESULTS: usize = 500;
+/// Stop walking after this many entries so huge trees stay responsive
+const RECENT_FILES_MAX_ENTRIES: usize = 50_000;
+
+/// One gitignore-style rule
+struct IgnoreRule {
+    pattern: glob::Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Rules containing a slash match the whole relative path, others any file name
+    anchored: bool,
+}
+
+/// Minimal gitignore-style matcher: `*`/`**` globs, trailing `/` for directories,
+/// leading `/` to anchor at the root and `!` to re-include. The last matching rule wins.
+struct IgnoreMatcher {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreMatcher {
+    fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        match glob::Pattern::new(line) {
+            Ok(pattern) => self.rules.push(IgnoreRule {
+                pattern,
+                negated,
+                dir_only,
+                anchored,
+            }),
+            Err(e) => log::warn!("Skipping invalid ignore pattern '{}': {}", line, e),
+        }
+    }
+
+    fn is_ignored(&self, relative: &std::path::Path, is_dir: bool) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        let name = relative
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let target = if rule.anchored { &relative_str } else { &name };
+            if rule.pattern.matches_with(target, options) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+
+    /// Like `is_ignored` for a file, but also honours rules on any parent directory
+    fn is_file_ignored(&self, relative: &std::path::Path) -> bool {
+        relative
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| self.is_ignored(dir, true))
+            || self.is_ignored(relative, false)
+    }
+}
+
+/// Walk the project for files modified after `since`, newest first.
+/// Ignored directories are pruned rather than descended into.
+fn scan_recently_modified_files(
+    root: &std::path::Path,
+    since: SystemTime,
+    matcher: &IgnoreMatcher,
+) -> Vec<PathBuf> {
+    let mut found: Vec<(SystemTime, PathBuf)> = Vec::new();
+    let walker = walkdir::WalkDir::new(root)
+        .max_depth(RECENT_FILES_MAX_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = match entry.path().strip_prefix(root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => return true,
+            };
+            entry.file_name() != ".git" && !matcher.is_ignored(relative, entry.file_type().is_dir())
+        });
+
+    for entry in walker.take(RECENT_FILES_MAX_ENTRIES) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let modified = match entry.metadata().ok().and_then(|m| m.modified().ok()) {
+            Some(modified) => modified,
+            None => continue,
+        };
+        if modified > since {
+            if let Ok(relative) = entry.path().strip_prefix(root) {
+                found.push((modified, relative.to_path_buf()));
+            }
+        }
+    }
+
+    found.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Gets files modified in the last N minutes for a session.
+/// `since` (RFC 3339) overrides `minutes`. When `scan_project` is set, the project tree is
+/// scanned as well as the files the session touched; this walks the whole tree, so it is
+/// off by default. `ignore_patterns` are gitignore-style and `respect_gitignore` also applies
+/// the project's root `.gitignore`. Depth and result count are capped.
 #[tauri::command]
 pub async fn get_recently_modified_files(
     app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
@@ -2974,8 +4779,12 @@ pub async fn get_recently_modified_files(
     project_id: String,
     project_path: String,
     minutes: i64,
+    ignore_patterns: Option<Vec<String>>,
+    since: Option<String>,
+    respect_gitignore: Option<bool>,
+    scan_project: Option<bool>,
 ) -> Result<Vec<String>, String> {
-    use chrono::{Duration, Utc};
+    use chrono::{DateTime, Duration, Utc};
 
     log::info!(
         "Getting files modified in the last {} minutes for session: {}",
@@ -2983,25 +4792,217 @@ pub async fn get_recently_modified_files(
         session_id
     );
 
+    let since = match since {
+        Some(since) => DateTime::parse_from_rfc3339(&since)
+            .map_err(|e| format!("Invalid since timestamp '{}': {}", since, e))?
+            .with_timezone(&Utc),
+        None => Utc::now() - Duration::minutes(minutes),
+    };
+
+    let root = PathBuf::from(&project_path);
+    let mut matcher = IgnoreMatcher::new();
+    if respect_gitignore.unwrap_or(false) {
+        if let Ok(gitignore) = fs::read_to_string(root.join(".gitignore")) {
+            for line in gitignore.lines() {
+                matcher.add(line);
+            }
+        }
+    }
+    for pattern in ignore_patterns.unwrap_or_default() {
+        matcher.add(&pattern);
+    }
+
     let manager = app
-        .get_or_create_manager(session_id, project_id, PathBuf::from(project_path))
+        .get_or_create_manager(session_id, project_id, root.clone())
         .await
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
-    let since = Utc::now() - Duration::minutes(minutes);
-    let modified_files = manager.get_files_modified_since(since).await;
+    let mut modified_files = manager.get_files_modified_since(since).await;
 
     // Also log the last modification time
     if let Some(last_mod) = manager.get_last_modification_time().await {
         log::info!("Last file modification was at: {}", last_mod);
     }
 
+    modified_files.retain(|path| {
+        let relative = path.strip_prefix(&root).unwrap_or(path);
+        !matcher.is_file_ignored(relative)
+    });
+
+    if scan_project.unwrap_or(false) {
+        let scan_root = root.clone();
+        let scanned = tokio::task::spawn_blocking(move || {
+            scan_recently_modified_files(&scan_root, SystemTime::from(since), &matcher)
+        })
+        .await
+        .map_err(|e| format!("Failed to scan project files: {}", e))?;
+
+        let mut seen: std::collections::HashSet<PathBuf> = modified_files.iter().cloned().collect();
+        for path in scanned {
+            if seen.insert(path.clone()) {
+                modified_files.push(path);
+            }
+        }
+    }
+    modified_files.truncate(RECENT_FILES_MAX_RESULTS);
+
     Ok(modified_files
         .into_iter()
         .map(|p| p.to_string_lossy().to_string())
         .collect())
 }
 
+/// Rough bytes-per-token ratio used for context estimates
+const CONTEXT_BYTES_PER_TOKEN: u64 = 4;
+/// Number of largest files reported in a context estimate
+const CONTEXT_LARGEST_FILES: usize = 20;
+/// Bytes sniffed at the start of a file to detect binary content
+const CONTEXT_BINARY_SNIFF_BYTES: usize = 8192;
+
+/// A single file's contribution to a context estimate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextFileEstimate {
+    pub path: String,
+    pub bytes: u64,
+    pub tokens: u64,
+}
+
+/// Estimated token cost of a project's text files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextEstimate {
+    pub total_tokens: u64,
+    pub total_bytes: u64,
+    pub file_count: usize,
+    pub skipped_binary: usize,
+    /// True when the walk stopped at the entry cap and the totals are a lower bound
+    pub truncated: bool,
+    pub largest_files: Vec<ContextFileEstimate>,
+}
+
+/// Whether a file looks binary (contains a NUL byte near the start)
+fn is_probably_binary(path: &std::path::Path) -> bool {
+    use std::io::Read;
+
+    let mut buffer = [0u8; CONTEXT_BINARY_SNIFF_BYTES];
+    match fs::File::open(path).and_then(|mut file| file.read(&mut buffer)) {
+        Ok(read) => buffer[..read].contains(&0),
+        Err(_) => true,
+    }
+}
+
+/// Walk the project and total up text files matching the include globs
+fn scan_context_estimate(
+    root: &std::path::Path,
+    include: &[glob::Pattern],
+    matcher: &IgnoreMatcher,
+) -> ContextEstimate {
+    let mut estimate = ContextEstimate {
+        total_tokens: 0,
+        total_bytes: 0,
+        file_count: 0,
+        skipped_binary: 0,
+        truncated: false,
+        largest_files: Vec::new(),
+    };
+    let mut files: Vec<ContextFileEstimate> = Vec::new();
+
+    let walker = walkdir::WalkDir::new(root)
+        .max_depth(RECENT_FILES_MAX_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = match entry.path().strip_prefix(root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => return true,
+            };
+            entry.file_name() != ".git" && !matcher.is_ignored(relative, entry.file_type().is_dir())
+        });
+
+    for (visited, entry) in walker.enumerate() {
+        if visited >= RECENT_FILES_MAX_ENTRIES {
+            estimate.truncated = true;
+            break;
+        }
+        let entry = match entry {
+            Ok(entry) if entry.file_type().is_file() => entry,
+            _ => continue,
+        };
+        let relative = match entry.path().strip_prefix(root) {
+            Ok(relative) => relative,
+            Err(_) => continue,
+        };
+        if !include.is_empty() && !include.iter().any(|p| p.matches_path(relative)) {
+            continue;
+        }
+        let bytes = match entry.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(_) => continue,
+        };
+        if is_probably_binary(entry.path()) {
+            estimate.skipped_binary += 1;
+            continue;
+        }
+
+        let tokens = bytes.div_ceil(CONTEXT_BYTES_PER_TOKEN);
+        estimate.total_bytes += bytes;
+        estimate.total_tokens += tokens;
+        estimate.file_count += 1;
+        files.push(ContextFileEstimate {
+            path: relative.to_string_lossy().to_string(),
+            bytes,
+            tokens,
+        });
+    }
+
+    files.sort_by_key(|f| std::cmp::Reverse(f.bytes));
+    files.truncate(CONTEXT_LARGEST_FILES);
+    estimate.largest_files = files;
+    estimate
+}
+
+/// Estimates how many tokens a project's text files would take up in context.
+/// `include_globs` select files by relative path (all files when empty); `ignore_globs` are
+/// gitignore-style and combined with the project's root `.gitignore`. Binary files are skipped
+/// and tokens are approximated as bytes / 4.
+#[tauri::command]
+pub async fn estimate_project_context_tokens(
+    project_path: String,
+    include_globs: Option<Vec<String>>,
+    ignore_globs: Option<Vec<String>>,
+) -> Result<ContextEstimate, String> {
+    let root = PathBuf::from(&project_path);
+    if !root.is_dir() {
+        return Err(format!("Project path is not a directory: {}", project_path));
+    }
+
+    let include = include_globs
+        .unwrap_or_default()
+        .iter()
+        .map(|g| glob::Pattern::new(g).map_err(|e| format!("Invalid glob '{}': {}", g, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut matcher = IgnoreMatcher::new();
+    if let Ok(gitignore) = fs::read_to_string(root.join(".gitignore")) {
+        for line in gitignore.lines() {
+            matcher.add(line);
+        }
+    }
+    for pattern in ignore_globs.unwrap_or_default() {
+        matcher.add(&pattern);
+    }
+
+    let estimate = tokio::task::spawn_blocking(move || scan_context_estimate(&root, &include, &matcher))
+        .await
+        .map_err(|e| format!("Failed to scan project files: {}", e))?;
+
+    log::info!(
+        "Estimated {} tokens across {} files in {}",
+        estimate.total_tokens,
+        estimate.file_count,
+        project_path
+    );
+    Ok(estimate)
+}
+
 /// Track session messages from the frontend for checkpointing
 #[tauri::command]
 pub async fn track_session_messages(
@@ -3026,13 +5027,23 @@ pub async fn track_session_messages(
         .await
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
+    let mut written_files = Vec::new();
     for message in messages {
+        written_files.extend(crate::checkpoint::manager::CheckpointManager::written_files(&message));
         manager
             .track_message(message)
             .await
             .map_err(|e| format!("Failed to track message: {}", e))?;
     }
 
+    let checkpointed = manager
+        .checkpoint_if_message_threshold()
+        .await
+        .map_err(|e| format!("Failed to create message-count checkpoint: {}", e))?;
+    if checkpointed.is_none() {
+        manager.schedule_file_write_checkpoint(written_files).await;
+    }
+
     Ok(())
 }
 
@@ -3165,6 +5176,392 @@ pub async fn validate_hook_command(command: String) -> Result<serde_json::Value,
     }
 }
 
+/// Shell builtins that never need to be found on PATH
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "cd", "echo", "eval", "exec", "exit", "export", "false", "printf", "read",
+    "return", "set", "source", "test", "true", "unset",
+];
+
+/// Whether a hook's executable can be found before the agent runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookDependencyCheck {
+    pub event: String,
+    pub matcher: Option<String>,
+    pub command: String,
+    /// First word of the command after any `VAR=value` assignments
+    pub executable: Option<String>,
+    pub resolved_path: Option<String>,
+    pub available: bool,
+    pub message: Option<String>,
+}
+
+/// Extracts the program a shell command line starts with, skipping leading env assignments
+fn hook_executable(command: &str) -> Option<String> {
+    let assignment = regex::Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*=").ok()?;
+    let mut rest = command.trim_start();
+    loop {
+        let (word, remainder) = match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let close = rest[1..].find(quote)? + 1;
+                (&rest[1..close], &rest[close + 1..])
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+        if !assignment.is_match(word) {
+            return Some(word.to_string());
+        }
+        rest = remainder.trim_start();
+    }
+}
+
+/// Checks every command hook's executable against the augmented agent PATH
+pub(crate) fn check_hook_dependencies_internal(hooks: &serde_json::Value) -> Vec<HookDependencyCheck> {
+    let mut entries = Vec::new();
+    collect_hook_entries(hooks, "agent", "", None, &mut entries);
+
+    entries
+        .into_iter()
+        .filter(|entry| entry.hook_type == "command")
+        .filter_map(|entry| {
+            let command = entry.command?;
+            let executable = hook_executable(&command);
+            let mut check = HookDependencyCheck {
+                event: entry.event,
+                matcher: entry.matcher,
+                command,
+                executable: executable.clone(),
+                resolved_path: None,
+                available: false,
+                message: None,
+            };
+
+            match executable {
+                None => check.message = Some("Could not determine the hook's executable".to_string()),
+                Some(exe) if SHELL_BUILTINS.contains(&exe.as_str()) => {
+                    check.available = true;
+                    check.message = Some("Shell builtin".to_string());
+                }
+                Some(exe) if exe.contains('$') => {
+                    // Resolved by the shell at run time, e.g. $CLAUDE_PROJECT_DIR/hooks/check.sh
+                    check.available = true;
+                    check.message = Some("Executable depends on shell variables; not checked".to_string());
+                }
+                Some(exe) => {
+                    let exe = match exe.strip_prefix("~/") {
+                        Some(rest) => dirs::home_dir()
+                            .map(|home| home.join(rest).to_string_lossy().to_string())
+                            .unwrap_or(exe),
+                        None => exe,
+                    };
+                    let path = crate::commands::agents::agent_spawn_path(&exe);
+                    check.resolved_path = crate::commands::agents::resolve_program_on_path(&exe, &path);
+                    check.available = check.resolved_path.is_some();
+                    if !check.available {
+                        check.message = Some(format!("'{}' was not found on PATH", exe));
+                    }
+                }
+            }
+            Some(check)
+        })
+        .collect()
+}
+
+/// Reports, per command hook, whether the program it runs can be found on PATH
+#[tauri::command]
+pub async fn check_hook_dependencies(hooks_json: String) -> Result<Vec<HookDependencyCheck>, String> {
+    let hooks: serde_json::Value = serde_json::from_str(&hooks_json)
+        .map_err(|e| format!("Failed to parse hooks: {}", e))?;
+    Ok(check_hook_dependencies_internal(&hooks))
+}
+
+/// A single hook command together with where it is configured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookEntry {
+    /// "user", "project", "local" or "agent"
+    pub source: String,
+    /// Settings file path, or the agent name for agent hooks
+    pub source_name: String,
+    pub agent_id: Option<i64>,
+    /// Hook event, e.g. "PreToolUse" or "Stop"
+    pub event: String,
+    pub matcher: Option<String>,
+    pub hook_type: String,
+    pub command: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+/// Flattens a `hooks` object (event -> matchers -> hooks) into entries
+fn collect_hook_entries(
+    hooks: &serde_json::Value,
+    source: &str,
+    source_name: &str,
+    agent_id: Option<i64>,
+    entries: &mut Vec<HookEntry>,
+) {
+    let events = match hooks.as_object() {
+        Some(events) => events,
+        None => return,
+    };
+
+    for (event, matchers) in events {
+        for matcher_entry in matchers.as_array().into_iter().flatten() {
+            let matcher = matcher_entry
+                .get("matcher")
+                .and_then(|m| m.as_str())
+                .filter(|m| !m.is_empty())
+                .map(|m| m.to_string());
+
+            // Entries without a nested "hooks" array are treated as a single hook
+            let hooks = match matcher_entry.get("hooks").and_then(|h| h.as_array()) {
+                Some(hooks) => hooks.iter().collect::<Vec<_>>(),
+                None => vec![matcher_entry],
+            };
+
+            for hook in hooks {
+                entries.push(HookEntry {
+                    source: source.to_string(),
+                    source_name: source_name.to_string(),
+                    agent_id,
+                    event: event.clone(),
+                    matcher: matcher.clone(),
+                    hook_type: hook
+                        .get("type")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("command")
+                        .to_string(),
+                    command: hook.get("command").and_then(|c| c.as_str()).map(|c| c.to_string()),
+                    timeout: hook.get("timeout").and_then(|t| t.as_u64()),
+                });
+            }
+        }
+    }
+}
+
+/// Lists hooks from user, project and local settings plus all agents
+#[tauri::command]
+pub async fn list_all_hooks(
+    db: tauri::State<'_, crate::commands::agents::AgentDb>,
+    project_path: Option<String>,
+) -> Result<Vec<HookEntry>, String> {
+    let mut settings_files = vec![(
+        "user",
+        get_claude_dir().map_err(|e| e.to_string())?.join("settings.json"),
+    )];
+    if let Some(project_path) = &project_path {
+        let claude_dir = PathBuf::from(project_path).join(".claude");
+        settings_files.push(("project", claude_dir.join("settings.json")));
+        settings_files.push(("local", claude_dir.join("settings.local.json")));
+    }
+
+    let mut entries = Vec::new();
+    for (source, path) in settings_files {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(settings) => {
+                if let Some(hooks) = settings.get("hooks") {
+                    collect_hook_entries(hooks, source, &path.to_string_lossy(), None, &mut entries);
+                }
+            }
+            Err(e) => log::warn!("Failed to parse hooks from {:?}: {}", path, e),
+        }
+    }
+
+    let agent_hooks: Vec<(i64, String, String)> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("SELECT id, name, hooks FROM agents WHERE hooks IS NOT NULL AND hooks != '' ORDER BY name")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+
+    for (agent_id, agent_name, hooks_json) in agent_hooks {
+        match serde_json::from_str::<serde_json::Value>(&hooks_json) {
+            Ok(hooks) => collect_hook_entries(&hooks, "agent", &agent_name, Some(agent_id), &mut entries),
+            Err(e) => log::warn!("Failed to parse hooks for agent {}: {}", agent_name, e),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// A potential problem found across hook sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookWarning {
+    /// "conflict", "duplicate" or "recursion"
+    pub kind: String,
+    pub event: String,
+    pub matcher: Option<String>,
+    pub message: String,
+    /// The hooks involved, with their sources
+    pub hooks: Vec<HookEntry>,
+}
+
+/// All hooks that apply to a project plus the warnings found among them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookAnalysis {
+    pub hooks: Vec<HookEntry>,
+    pub warnings: Vec<HookWarning>,
+}
+
+/// Matchers that apply to every tool
+fn is_wildcard_matcher(matcher: &Option<String>) -> bool {
+    matches!(matcher.as_deref(), None | Some("*") | Some(".*"))
+}
+
+/// Whether a hook command launches TermiClaude itself or the Claude CLI, which fires hooks again
+fn hook_may_recurse(command: &str, app_exe: Option<&str>) -> bool {
+    let exe = match hook_executable(command) {
+        Some(exe) => exe,
+        None => return false,
+    };
+    let name = std::path::Path::new(&exe)
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name == "claude" || app_exe.is_some_and(|app| name == app)
+}
+
+/// Aggregates hooks from user, project, local and agent sources and flags
+/// overlapping hooks on the same event, duplicates and hooks that may recurse
+#[tauri::command]
+pub async fn analyze_hooks(
+    db: tauri::State<'_, crate::commands::agents::AgentDb>,
+    project_path: Option<String>,
+) -> Result<HookAnalysis, String> {
+    let hooks = list_all_hooks(db, project_path).await?;
+    let mut warnings = Vec::new();
+
+    let source_key = |hook: &HookEntry| format!("{}:{}", hook.source, hook.source_name);
+    let command_hooks: Vec<&HookEntry> = hooks
+        .iter()
+        .filter(|hook| hook.hook_type == "command" && hook.command.is_some())
+        .collect();
+
+    let mut events: Vec<&str> = command_hooks.iter().map(|hook| hook.event.as_str()).collect();
+    events.sort();
+    events.dedup();
+
+    for event in events {
+        let on_event: Vec<&HookEntry> = command_hooks
+            .iter()
+            .copied()
+            .filter(|hook| hook.event == event)
+            .collect();
+
+        // One group per concrete matcher (plus wildcard hooks, which overlap everything)
+        let mut matchers: Vec<Option<String>> = on_event
+            .iter()
+            .filter(|hook| !is_wildcard_matcher(&hook.matcher))
+            .map(|hook| hook.matcher.clone())
+            .collect();
+        matchers.sort();
+        matchers.dedup();
+        matchers.push(None);
+
+        for matcher in matchers {
+            let group: Vec<HookEntry> = on_event
+                .iter()
+                .filter(|hook| is_wildcard_matcher(&hook.matcher) || hook.matcher == matcher)
+                .map(|hook| (*hook).clone())
+                .collect();
+            let mut sources: Vec<String> = group.iter().map(source_key).collect();
+            sources.sort();
+            sources.dedup();
+
+            // Overlapping hooks only conflict when they come from different sources
+            if sources.len() >= 2 {
+                let target = matcher.clone().unwrap_or_else(|| "all tools".to_string());
+                let message = if event == "PreToolUse" {
+                    format!(
+                        "{} hooks from {} sources run before {}; they may disagree on blocking the call",
+                        group.len(),
+                        sources.len(),
+                        target
+                    )
+                } else {
+                    format!(
+                        "{} hooks from {} sources run on {} for {}; their order is not guaranteed",
+                        group.len(),
+                        sources.len(),
+                        event,
+                        target
+                    )
+                };
+                warnings.push(HookWarning {
+                    kind: "conflict".to_string(),
+                    event: event.to_string(),
+                    matcher: matcher.clone(),
+                    message,
+                    hooks: group.clone(),
+                });
+            }
+
+            // Duplicates are reported once, in the group of their own matcher, even when
+            // every copy comes from the same settings file
+            let own: Vec<&HookEntry> = group
+                .iter()
+                .filter(|hook| {
+                    let normalized = if is_wildcard_matcher(&hook.matcher) { None } else { hook.matcher.clone() };
+                    normalized == matcher
+                })
+                .collect();
+            let mut seen: Vec<&str> = Vec::new();
+            for hook in &own {
+                let command = hook.command.as_deref().unwrap_or_default();
+                if seen.contains(&command) {
+                    continue;
+                }
+                seen.push(command);
+                let copies: Vec<HookEntry> = own
+                    .iter()
+                    .filter(|other| other.command.as_deref() == Some(command))
+                    .map(|other| (*other).clone())
+                    .collect();
+                if copies.len() > 1 {
+                    warnings.push(HookWarning {
+                        kind: "duplicate".to_string(),
+                        event: event.to_string(),
+                        matcher: matcher.clone(),
+                        message: format!("'{}' is configured {} times and will run repeatedly", command, copies.len()),
+                        hooks: copies,
+                    });
+                }
+            }
+        }
+    }
+
+    let app_exe = std::env::current_exe().ok().and_then(|path| {
+        path.file_stem().map(|n| n.to_string_lossy().to_lowercase())
+    });
+    for hook in &command_hooks {
+        let command = hook.command.as_deref().unwrap_or_default();
+        if hook_may_recurse(command, app_exe.as_deref()) {
+            warnings.push(HookWarning {
+                kind: "recursion".to_string(),
+                event: hook.event.clone(),
+                matcher: hook.matcher.clone(),
+                message: format!(
+                    "Hook from {} runs '{}', which can trigger the same hooks again",
+                    hook.source_name, command
+                ),
+                hooks: vec![(*hook).clone()],
+            });
+        }
+    }
+
+    log::info!("Analyzed {} hooks, {} warnings", hooks.len(), warnings.len());
+    Ok(HookAnalysis { hooks, warnings })
+}
+
 /// Deletes an entire project and all its sessions
 #[tauri::command]
 pub async fn delete_project(
@@ -3277,4 +5674,69 @@ pub async fn delete_session(
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_env_group_writes_default_model_vars() {
+        let conn = rusqlite::Connection::open_in_memory().expect("Failed to open in-memory database");
+        conn.execute_batch(
+            "CREATE TABLE environment_variable_groups (id INTEGER PRIMARY KEY, name TEXT, enabled BOOLEAN, sort_order INTEGER);
+             CREATE TABLE environment_variables (id INTEGER PRIMARY KEY, key TEXT, value TEXT, enabled BOOLEAN, group_id INTEGER, sort_order INTEGER);
+             INSERT INTO environment_variable_groups VALUES (1, 'provider', 1, 0);
+             INSERT INTO environment_variables (key, value, enabled, group_id, sort_order) VALUES
+                ('ANTHROPIC_AUTH_TOKEN', 'token', 1, 1, 0),
+                ('ANTHROPIC_DEFAULT_HAIKU_MODEL', 'haiku-model', 1, 1, 1),
+                ('ANTHROPIC_DEFAULT_OPUS_MODEL', 'opus-model', 1, 1, 2),
+                ('ANTHROPIC_DEFAULT_SONNET_MODEL', 'sonnet-model', 1, 1, 3),
+                ('MID_1', 'sonnet-model', 1, 1, 4);",
+        )
+        .expect("Failed to set up environment variables");
+
+        let env_vars = crate::commands::agents::get_enabled_environment_variables_internal(&conn)
+            .expect("Failed to read enabled environment variables");
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let settings_path = temp_dir.path().join("settings.json");
+        std::fs::write(&settings_path, r#"{"env":{"EXTERNAL_VAR":"keep","HTTPS_PROXY":"http://old"}}"#)
+            .expect("Failed to write settings");
+
+        let previously_written = std::collections::BTreeSet::from(["HTTPS_PROXY".to_string()]);
+        write_env_vars_to_settings(&settings_path, &env_vars, Some("sonnet-model"), &previously_written)
+            .expect("Failed to write settings");
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&settings_path).unwrap()).unwrap();
+        let env = &written["env"];
+        assert_eq!(env["ANTHROPIC_DEFAULT_HAIKU_MODEL"], "haiku-model");
+        assert_eq!(env["ANTHROPIC_DEFAULT_OPUS_MODEL"], "opus-model");
+        assert_eq!(env["ANTHROPIC_DEFAULT_SONNET_MODEL"], "sonnet-model");
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "token");
+        assert_eq!(env["ANTHROPIC_MODEL"], "sonnet-model");
+        assert_eq!(env["EXTERNAL_VAR"], "keep");
+        assert!(env.get("HTTPS_PROXY").is_none());
+        assert!(env.get("MID_1").is_none());
+    }
+
+    #[test]
+    fn test_ignore_matcher_gitignore_rules() {
+        let mut matcher = IgnoreMatcher::new();
+        for line in ["# build output", "target/", "node_modules", "*.log", "!keep.log", "/dist"] {
+            matcher.add(line);
+        }
+
+        assert!(matcher.is_file_ignored(std::path::Path::new("target/debug/app")));
+        assert!(matcher.is_file_ignored(std::path::Path::new("web/node_modules/react/index.js")));
+        assert!(matcher.is_file_ignored(std::path::Path::new("logs/server.log")));
+        assert!(matcher.is_file_ignored(std::path::Path::new("dist/bundle.js")));
+        assert!(!matcher.is_file_ignored(std::path::Path::new("logs/keep.log")));
+        assert!(!matcher.is_file_ignored(std::path::Path::new("web/dist/bundle.js")));
+        assert!(!matcher.is_file_ignored(std::path::Path::new("src/main.rs")));
+        // "target/" only applies to directories
+        assert!(!matcher.is_ignored(std::path::Path::new("target"), false));
+    }
+}
@@ -0,0 +1,392 @@
+use log::info;
+use regex::Regex;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::agents::{get_enabled_environment_variables, mask_secret, AgentDb};
+use super::proxy::get_proxy_settings;
+use super::settings_monitor::is_secret_key;
+
+/// How much of the end of each log file goes into a diagnostic bundle
+const DIAGNOSTIC_LOG_TAIL_BYTES: u64 = 512 * 1024;
+
+/// Keys of `~/.claude.json` MCP server entries whose values are always secret
+const MCP_SECRET_CONTAINERS: &[&str] = &["env", "headers"];
+
+/// Mask string values under secret-looking keys; everything under `env`/`headers` is masked
+fn redact_json(value: &mut Value, force: bool) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let secret = force || is_secret_key(key);
+                match child {
+                    Value::String(s) if secret => *s = mask_secret(s),
+                    _ => redact_json(child, secret || MCP_SECRET_CONTAINERS.contains(&key.as_str())),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_json(item, force);
+            }
+        }
+        Value::String(s) if force => *s = mask_secret(s),
+        _ => {}
+    }
+}
+
+/// The `user:password` part of a proxy URL, if any
+fn url_credentials(url: &str) -> Option<&str> {
+    match (url.find("://"), url.rfind('@')) {
+        (Some(scheme_end), Some(at)) if at > scheme_end + 3 => Some(&url[scheme_end + 3..at]),
+        _ => None,
+    }
+}
+
+/// Replace `user:password@` in a proxy URL
+fn redact_url_credentials(url: &str) -> String {
+    match (url.find("://"), url.rfind('@')) {
+        (Some(scheme_end), Some(at)) if at > scheme_end => {
+            format!("{}://***@{}", &url[..scheme_end], &url[at + 1..])
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Record the credentials and secret query values of a URL and return it with them masked
+fn redact_url(url: &str, secrets: &mut Vec<String>) -> String {
+    if let Some(credentials) = url_credentials(url) {
+        secrets.push(credentials.to_string());
+        if let Some((_, password)) = credentials.split_once(':') {
+            secrets.push(password.to_string());
+        }
+    }
+    let url = redact_url_credentials(url);
+    match url.split_once('?') {
+        Some((base, query)) => {
+            let query: Vec<String> = query
+                .split('&')
+                .map(|pair| match pair.split_once('=') {
+                    Some((name, value)) if is_secret_key(name) => {
+                        secrets.push(value.to_string());
+                        format!("{}={}", name, mask_secret(value))
+                    }
+                    _ => pair.to_string(),
+                })
+                .collect();
+            format!("{}?{}", base, query.join("&"))
+        }
+        None => url,
+    }
+}
+
+/// A command-line flag such as `--api-key` or `--token=...`
+fn is_secret_flag(arg: &str) -> bool {
+    arg.starts_with('-')
+        && is_secret_key(arg.trim_start_matches('-').split('=').next().unwrap_or_default())
+}
+
+/// Record and (when `redact`) mask secrets in an MCP server's `args`: the value following
+/// a secret-looking flag, `--flag=value` forms and URL credentials/query secrets
+fn scrub_mcp_args(args: &mut [Value], secrets: &mut Vec<String>, redact: bool) {
+    let mut after_secret_flag = false;
+    for arg in args.iter_mut() {
+        let s = match arg {
+            Value::String(s) => s,
+            _ => {
+                after_secret_flag = false;
+                continue;
+            }
+        };
+        if after_secret_flag && !s.starts_with('-') {
+            after_secret_flag = false;
+            secrets.push(s.clone());
+            if redact {
+                *s = mask_secret(s);
+            }
+            continue;
+        }
+        after_secret_flag = false;
+
+        if is_secret_flag(s) {
+            match s.split_once('=') {
+                Some((flag, value)) => {
+                    secrets.push(value.to_string());
+                    if redact {
+                        *s = format!("{}={}", flag, mask_secret(value));
+                    }
+                }
+                None => after_secret_flag = true,
+            }
+        } else if s.contains("://") {
+            let masked = redact_url(s, secrets);
+            if redact {
+                *s = masked;
+            }
+        }
+    }
+}
+
+/// Record every MCP server secret (`env`/`headers` values, secret args, URL credentials) so logs
+/// can be scrubbed of them, and mask them in `servers` when `redact` is set
+fn scrub_mcp_servers(servers: &mut Value, secrets: &mut Vec<String>, redact: bool) {
+    let servers = match servers.as_object_mut() {
+        Some(servers) => servers,
+        None => return,
+    };
+    for server in servers.values_mut() {
+        for container in MCP_SECRET_CONTAINERS {
+            if let Some(values) = server.get(*container).and_then(|v| v.as_object()) {
+                secrets.extend(values.values().filter_map(|v| v.as_str()).map(str::to_string));
+            }
+        }
+        if let Some(args) = server.get_mut("args").and_then(|a| a.as_array_mut()) {
+            scrub_mcp_args(args, secrets, redact);
+        }
+        if let Some(Value::String(url)) = server.get_mut("url") {
+            let masked = redact_url(url, secrets);
+            if redact {
+                *url = masked;
+            }
+        }
+        if redact {
+            redact_json(server, false);
+        }
+    }
+}
+
+/// Replace known secret values and API-key-looking strings in log text
+fn redact_text(text: &str, secrets: &[String]) -> String {
+    static API_KEY: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let api_key = API_KEY.get_or_init(|| Regex::new(r"sk-[A-Za-z0-9_\-]{16,}").unwrap());
+
+    let mut text = text.to_string();
+    // Too short to replace without mangling unrelated text
+    for secret in secrets.iter().filter(|s| s.len() >= 8) {
+        text = text.replace(secret.as_str(), &mask_secret(secret));
+    }
+    api_key.replace_all(&text, "sk-***").to_string()
+}
+
+/// Last `DIAGNOSTIC_LOG_TAIL_BYTES` of a file, lossily decoded
+fn read_file_tail(path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(DIAGNOSTIC_LOG_TAIL_BYTES)))
+        .ok()?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// SQLite user_version plus the columns of every table in agents.db
+fn database_schema(conn: &Connection) -> Result<Value, String> {
+    let user_version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let tables: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .map_err(|e| e.to_string())?;
+        let names = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        names
+    };
+
+    let mut columns = serde_json::Map::new();
+    for table in tables {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info(\"{}\")", table.replace('"', "\"\"")))
+            .map_err(|e| e.to_string())?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        columns.insert(table, json!(names));
+    }
+
+    Ok(json!({ "user_version": user_version, "tables": columns }))
+}
+
+/// Detected Claude installations and the binary the app would use
+fn claude_installations(app: &AppHandle) -> Value {
+    json!({
+        "selected": crate::claude_binary::find_claude_binary(app).ok(),
+        "installations": crate::claude_binary::discover_claude_installations(),
+    })
+}
+
+/// MCP servers from `~/.claude.json`, unredacted: the user-scope `mcpServers` and the
+/// local-scope servers of every project that has any
+fn mcp_config() -> Value {
+    let config = dirs::home_dir()
+        .map(|home| home.join(".claude.json"))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .unwrap_or_else(|| json!({}));
+
+    let projects: serde_json::Map<String, Value> = config
+        .get("projects")
+        .and_then(|p| p.as_object())
+        .map(|projects| {
+            projects
+                .iter()
+                .filter_map(|(path, project)| {
+                    let servers = project.get("mcpServers")?;
+                    servers
+                        .as_object()
+                        .filter(|s| !s.is_empty())
+                        .map(|_| (path.clone(), servers.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "user": config.get("mcpServers").cloned().unwrap_or_else(|| json!({})),
+        "projects": projects,
+    })
+}
+
+fn to_pretty_bytes(value: &Value) -> Vec<u8> {
+    serde_json::to_vec_pretty(value).unwrap_or_default()
+}
+
+/// Write a zip with system info, Claude installations, enabled env var keys, MCP config,
+/// proxy settings, the database schema and (optionally) recent logs. Environment variable
+/// values are always masked; other secrets are masked unless `redact_secrets` is false
+/// (default true). Returns the path of the written bundle.
+#[tauri::command]
+pub async fn generate_diagnostic_bundle(
+    app: AppHandle,
+    include_logs: bool,
+    redact_secrets: Option<bool>,
+) -> Result<String, String> {
+    let redact_secrets = redact_secrets.unwrap_or(true);
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+
+    entries.push((
+        "system.json".to_string(),
+        to_pretty_bytes(&json!({
+            "app_version": app.package_info().version.to_string(),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "family": std::env::consts::FAMILY,
+            "generated_at": chrono::Utc::now().to_rfc3339(),
+            "secrets_redacted": redact_secrets,
+        })),
+    ));
+
+    // Values that must not leak through the logs
+    let mut secrets: Vec<String> = Vec::new();
+
+    let env_vars = get_enabled_environment_variables(app.state::<AgentDb>()).await?;
+    let mut env_entries: Vec<(&String, &String)> = env_vars.iter().collect();
+    env_entries.sort();
+    let env_json: serde_json::Map<String, Value> = env_entries
+        .into_iter()
+        .map(|(key, value)| {
+            if is_secret_key(key) {
+                secrets.push(value.clone());
+            }
+            (key.clone(), Value::String(mask_secret(value)))
+        })
+        .collect();
+    entries.push(("environment_variables.json".to_string(), to_pretty_bytes(&Value::Object(env_json))));
+
+    let mut proxy = get_proxy_settings(app.state::<AgentDb>()).await?;
+    for url in [&proxy.http_proxy, &proxy.https_proxy, &proxy.all_proxy].into_iter().flatten() {
+        if let Some(credentials) = url_credentials(url) {
+            secrets.push(credentials.to_string());
+            if let Some((_, password)) = credentials.split_once(':') {
+                secrets.push(password.to_string());
+            }
+        }
+    }
+    if redact_secrets {
+        for url in [&mut proxy.http_proxy, &mut proxy.https_proxy, &mut proxy.all_proxy]
+            .into_iter()
+            .flatten()
+        {
+            *url = redact_url_credentials(url);
+        }
+    }
+    entries.push((
+        "proxy.json".to_string(),
+        serde_json::to_vec_pretty(&proxy).map_err(|e| e.to_string())?,
+    ));
+
+    let mut mcp_servers = mcp_config();
+    if let Some(user) = mcp_servers.get_mut("user") {
+        scrub_mcp_servers(user, &mut secrets, redact_secrets);
+    }
+    if let Some(projects) = mcp_servers.get_mut("projects").and_then(|p| p.as_object_mut()) {
+        for servers in projects.values_mut() {
+            scrub_mcp_servers(servers, &mut secrets, redact_secrets);
+        }
+    }
+    entries.push(("mcp_servers.json".to_string(), to_pretty_bytes(&mcp_servers)));
+
+    let schema = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        database_schema(&conn)?
+    };
+    entries.push(("database_schema.json".to_string(), to_pretty_bytes(&schema)));
+
+    let app_for_install = app.clone();
+    let installations = tokio::task::spawn_blocking(move || claude_installations(&app_for_install))
+        .await
+        .map_err(|e| e.to_string())?;
+    entries.push(("claude_installations.json".to_string(), to_pretty_bytes(&installations)));
+
+    if include_logs {
+        if let Some(log_path) = crate::logger::log_file_path() {
+            for path in [log_path.with_extension("log.old"), log_path] {
+                let name = match path.file_name() {
+                    Some(name) => format!("logs/{}", name.to_string_lossy()),
+                    None => continue,
+                };
+                if let Some(content) = read_file_tail(&path) {
+                    let content = if redact_secrets { redact_text(&content, &secrets) } else { content };
+                    entries.push((name, content.into_bytes()));
+                }
+            }
+        }
+    }
+
+    let bundle_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("diagnostics");
+    std::fs::create_dir_all(&bundle_dir)
+        .map_err(|e| format!("Failed to create diagnostics directory: {}", e))?;
+    let bundle_path: PathBuf = bundle_dir.join(format!(
+        "termiclaude-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let file = std::fs::File::create(&bundle_path)
+        .map_err(|e| format!("Failed to create {}: {}", bundle_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish diagnostic bundle: {}", e))?;
+
+    info!("Wrote diagnostic bundle to {}", bundle_path.display());
+    Ok(bundle_path.to_string_lossy().to_string())
+}
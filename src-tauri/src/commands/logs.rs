@@ -0,0 +1,147 @@
+use log::Level;
+use regex::Regex;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+use tauri::State;
+
+use super::agents::AgentDb;
+use crate::logger::{current_level, log_file_path, parse_level, set_runtime_level};
+
+/// app_settings key used to persist the runtime log level
+pub const LOG_LEVEL_SETTING_KEY: &str = "log_level";
+
+/// A single parsed line from the application log file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+}
+
+/// Parse raw log file content into entries.
+/// Lines that don't start with a `[timestamp] [LEVEL]` header are treated as
+/// continuations of the previous entry (e.g. multi-line error messages).
+fn parse_log_lines(content: &str) -> Vec<LogEntry> {
+    let header = Regex::new(r"^\[([^\]]+)\] \[(ERROR|WARN|INFO|DEBUG|TRACE)\] (.*)$").unwrap();
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for line in content.lines() {
+        if let Some(caps) = header.captures(line) {
+            entries.push(LogEntry {
+                timestamp: caps[1].to_string(),
+                level: caps[2].to_string(),
+                message: caps[3].to_string(),
+            });
+        } else if let Some(last) = entries.last_mut() {
+            last.message.push('\n');
+            last.message.push_str(line);
+        }
+    }
+
+    entries
+}
+
+/// How much of the end of the log file is read first; doubled until enough entries are found
+const LOG_TAIL_CHUNK_BYTES: u64 = 256 * 1024;
+
+/// Read the last `bytes` of a file, dropping the partial line it starts in.
+/// Returns the text and whether it reaches back to the start of the file.
+fn read_log_tail(path: &Path, bytes: u64) -> Result<(String, bool), String> {
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open log file: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log file: {}", e))?
+        .len();
+    let start = len.saturating_sub(bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to read log file: {}", e))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .map_err(|e| format!("Failed to read log file: {}", e))?;
+
+    let mut content = String::from_utf8_lossy(&buffer).to_string();
+    if start > 0 {
+        content = match content.split_once('\n') {
+            Some((_, rest)) => rest.to_string(),
+            None => String::new(),
+        };
+    }
+    Ok((content, start == 0))
+}
+
+/// Read the most recent log entries written by the unified logger.
+/// Only the end of the file is read, growing the window until `lines` entries match.
+#[tauri::command]
+pub async fn get_recent_logs(
+    lines: usize,
+    min_level: Option<String>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = match min_level {
+        Some(level) if !level.is_empty() => {
+            Some(Level::from_str(&level).map_err(|_| format!("Invalid log level: {}", level))?)
+        }
+        _ => None,
+    };
+
+    let path = match log_file_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut tail_bytes = LOG_TAIL_CHUNK_BYTES;
+    let mut entries: Vec<LogEntry> = loop {
+        let (content, whole_file) = read_log_tail(&path, tail_bytes)?;
+        let entries: Vec<LogEntry> = parse_log_lines(&content)
+            .into_iter()
+            .filter(|entry| match min_level {
+                Some(min) => Level::from_str(&entry.level)
+                    .map(|level| level <= min)
+                    .unwrap_or(true),
+                None => true,
+            })
+            .collect();
+        if whole_file || entries.len() > lines {
+            break entries;
+        }
+        tail_bytes *= 2;
+    };
+
+    if entries.len() > lines {
+        entries.drain(..entries.len() - lines);
+    }
+
+    Ok(entries)
+}
+
+/// Change the log level at runtime and persist it for the next launch
+#[tauri::command]
+pub async fn set_log_level(db: State<'_, AgentDb>, level: String) -> Result<String, String> {
+    let filter = parse_level(&level).ok_or_else(|| {
+        format!(
+            "Invalid log level: {}. Expected one of: off, error, warn, info, debug, trace",
+            level
+        )
+    })?;
+
+    set_runtime_level(filter);
+
+    let value = filter.to_string().to_lowercase();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        params![LOG_LEVEL_SETTING_KEY, value],
+    )
+    .map_err(|e| format!("Failed to save log level: {}", e))?;
+
+    Ok(value)
+}
+
+/// Get the currently active log level
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(current_level().to_string().to_lowercase())
+}
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use dirs;
-use log::{error, info};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -86,6 +86,256 @@ fn get_claude_config_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// ~/.claude.json 中 CLI 记录被拒绝/批准的 .mcp.json 服务器的字段（位于 projects.<path> 下）
+const PROJECT_DISABLED_KEY: &str = "disabledMcpjsonServers";
+const PROJECT_ENABLED_KEY: &str = "enabledMcpjsonServers";
+
+/// CLI 没有 user/local 作用域的禁用开关，禁用时把服务器从 ~/.claude.json 移出，
+/// 配置暂存在 app_settings 的这个键下（{"user": {name: config}, "local": {path: {name: config}}}），启用时再放回
+const PARKED_SERVERS_SETTING: &str = "mcp_parked_servers";
+
+/// 读取暂存的 user/local 服务器配置
+async fn read_parked_servers(app: &AppHandle) -> serde_json::Value {
+    crate::commands::storage::get_app_setting(app.clone(), PARKED_SERVERS_SETTING.to_string())
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_else(|| serde_json::json!({}))
+}
+
+/// 某个作用域下暂存的服务器（名称 -> 配置）
+fn parked_scope_servers(
+    parked: &serde_json::Value,
+    scope: &str,
+    project_path: &str,
+) -> serde_json::Map<String, serde_json::Value> {
+    let container = match scope {
+        "user" => parked.get("user"),
+        "local" => parked.get("local").and_then(|l| l.get(project_path)),
+        _ => None,
+    };
+    container
+        .and_then(|c| c.as_object())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 在暂存区中放入或取出一个服务器配置，返回取出的配置
+async fn update_parked_server(
+    app: &AppHandle,
+    scope: &str,
+    project_path: &str,
+    name: &str,
+    config: Option<serde_json::Value>,
+) -> Result<Option<serde_json::Value>, String> {
+    let mut parked = read_parked_servers(app).await;
+    let root = parked
+        .as_object_mut()
+        .ok_or("Parked MCP servers setting is not a JSON object")?;
+    let scope_entry = root.entry(scope.to_string()).or_insert_with(|| serde_json::json!({}));
+    let container = match scope {
+        "user" => scope_entry,
+        "local" => scope_entry
+            .as_object_mut()
+            .ok_or("Parked local servers are not an object")?
+            .entry(project_path.to_string())
+            .or_insert_with(|| serde_json::json!({})),
+        other => return Err(format!("Scope '{}' cannot park servers", other)),
+    }
+    .as_object_mut()
+    .ok_or("Parked servers are not an object")?;
+
+    let previous = match config {
+        Some(config) => container.insert(name.to_string(), config),
+        None => container.remove(name),
+    };
+
+    let value = serde_json::to_string(&parked)
+        .map_err(|e| format!("Failed to serialize parked MCP servers: {}", e))?;
+    crate::commands::storage::save_app_setting(app.clone(), PARKED_SERVERS_SETTING.to_string(), value).await?;
+    Ok(previous)
+}
+
+/// 获取某个作用域中被禁用的服务器名称：project 作用域读取 CLI 的 disabledMcpjsonServers，
+/// user/local 作用域为暂存区中的服务器
+fn scope_disabled_servers(
+    config: &serde_json::Value,
+    parked: &serde_json::Value,
+    scope: &str,
+    project_path: &str,
+) -> Vec<String> {
+    match scope {
+        "project" => config
+            .get("projects")
+            .and_then(|p| p.get(project_path))
+            .and_then(|c| c.get(PROJECT_DISABLED_KEY))
+            .and_then(|list| list.as_array())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => parked_scope_servers(parked, scope, project_path).keys().cloned().collect(),
+    }
+}
+
+/// 暂存区中的 user/local 服务器，作为已禁用的条目列出
+fn parked_server_entries(parked: &serde_json::Value, project_path: &str) -> Vec<MCPServer> {
+    let mut servers = Vec::new();
+    for scope in ["user", "local"] {
+        for (name, config) in parked_scope_servers(parked, scope, project_path) {
+            let command = config.get("command").and_then(|c| c.as_str()).map(|command| {
+                let args = config
+                    .get("args")
+                    .and_then(|a| a.as_array())
+                    .map(|args| args.iter().filter_map(|a| a.as_str()).collect::<Vec<_>>().join(" "))
+                    .unwrap_or_default();
+                format!("{} {}", command, args).trim().to_string()
+            });
+            servers.push(MCPServer {
+                name,
+                transport: config.get("type").and_then(|t| t.as_str()).unwrap_or("stdio").to_string(),
+                command,
+                args: vec![],
+                env: HashMap::new(),
+                url: config.get("url").and_then(|u| u.as_str()).map(|u| u.to_string()),
+                scope: scope.to_string(),
+                is_active: false,
+                disabled: true,
+                status: ServerStatus {
+                    running: false,
+                    error: None,
+                    last_checked: None,
+                },
+            });
+        }
+    }
+    servers
+}
+
+/// 在 projects.<path> 的 disabledMcpjsonServers/enabledMcpjsonServers 中记录 .mcp.json 服务器的选择
+fn set_project_mcpjson_disabled(
+    config: &mut serde_json::Value,
+    project_path: &str,
+    names: &[String],
+    disabled: bool,
+) -> Result<(), String> {
+    let project = config
+        .as_object_mut()
+        .ok_or("Claude config is not a JSON object")?
+        .entry("projects")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("Claude config 'projects' is not an object")?
+        .entry(project_path.to_string())
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("Claude project config is not an object")?;
+
+    let (add_to, remove_from) = if disabled {
+        (PROJECT_DISABLED_KEY, PROJECT_ENABLED_KEY)
+    } else {
+        (PROJECT_ENABLED_KEY, PROJECT_DISABLED_KEY)
+    };
+
+    if let Some(list) = project.get_mut(remove_from).and_then(|l| l.as_array_mut()) {
+        list.retain(|v| !v.as_str().is_some_and(|s| names.iter().any(|n| n == s)));
+    }
+    let list = project
+        .entry(add_to)
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or_else(|| format!("'{}' is not an array", add_to))?;
+    for name in names {
+        if !list.iter().any(|v| v.as_str() == Some(name.as_str())) {
+            list.push(serde_json::Value::String(name.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// 同步 project 作用域服务器的禁用状态到 ~/.claude.json，CLI 据此决定是否加载 .mcp.json 中的服务器
+async fn sync_project_mcpjson_choice(project_path: &str, names: &[String], disabled: bool) -> Result<(), String> {
+    let content = mcp_read_claude_global_config().await?;
+    let mut config: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse Claude config: {}", e))?;
+    set_project_mcpjson_disabled(&mut config, project_path, names, disabled)?;
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize Claude config: {}", e))?;
+    mcp_write_claude_global_config(content).await?;
+    Ok(())
+}
+
+/// 把 user/local 作用域的服务器移入或移出 ~/.claude.json 的 mcpServers
+async fn set_scope_server_disabled(
+    app: &AppHandle,
+    scope: &str,
+    project_path: &str,
+    name: &str,
+    disabled: bool,
+) -> Result<(), String> {
+    let content = mcp_read_claude_global_config().await?;
+    let mut config: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse Claude config: {}", e))?;
+
+    let root = config
+        .as_object_mut()
+        .ok_or("Claude config is not a JSON object")?;
+    let container = match scope {
+        "user" => root,
+        "local" => root
+            .entry("projects")
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+            .ok_or("Claude config 'projects' is not an object")?
+            .entry(project_path.to_string())
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+            .ok_or("Claude project config is not an object")?,
+        other => return Err(format!("Scope '{}' cannot park servers", other)),
+    };
+    let servers = container
+        .entry("mcpServers")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("'mcpServers' is not an object")?;
+
+    if disabled {
+        let server_config = match servers.remove(name) {
+            Some(server_config) => server_config,
+            None => {
+                let parked = read_parked_servers(app).await;
+                if parked_scope_servers(&parked, scope, project_path).contains_key(name) {
+                    return Ok(());
+                }
+                return Err(format!("Server '{}' not found in {} scope", name, scope));
+            }
+        };
+        // 先暂存再写回配置，写入失败时配置不会丢失
+        update_parked_server(app, scope, project_path, name, Some(server_config)).await?;
+    } else {
+        let parked = read_parked_servers(app).await;
+        let server_config = match parked_scope_servers(&parked, scope, project_path).remove(name) {
+            Some(server_config) => server_config,
+            None if servers.contains_key(name) => return Ok(()),
+            None => return Err(format!("Server '{}' is not disabled in {} scope", name, scope)),
+        };
+        servers.entry(name.to_string()).or_insert(server_config);
+    }
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize Claude config: {}", e))?;
+    mcp_write_claude_global_config(content).await?;
+
+    if !disabled {
+        update_parked_server(app, scope, project_path, name, None).await?;
+    }
+    Ok(())
+}
+
 /// 读取Claude全局配置
 #[tauri::command]
 pub async fn mcp_read_claude_global_config() -> Result<String, String> {
@@ -145,12 +395,69 @@ pub async fn mcp_write_claude_global_config(content: String) -> Result<String, S
     }
 }
 
-/// 备份Claude配置文件
+/// 默认保留的备份数量
+const DEFAULT_CONFIG_BACKUPS_KEPT: usize = 10;
+
+/// Claude配置文件的一个备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    /// 备份时间（Unix 秒）
+    pub timestamp: u64,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// 列出某个配置文件的所有备份（`<name>.backup.<timestamp>`），最新的在前
+fn list_config_backups(config_path: &std::path::Path) -> Vec<BackupInfo> {
+    let (dir, file_name) = match (config_path.parent(), config_path.file_name()) {
+        (Some(dir), Some(name)) => (dir, name.to_string_lossy().to_string()),
+        _ => return Vec::new(),
+    };
+    let prefix = format!("{}.backup.", file_name);
+
+    let mut backups: Vec<BackupInfo> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let timestamp = name.strip_prefix(&prefix)?.parse::<u64>().ok()?;
+                    let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+                    let created_at = chrono::DateTime::from_timestamp(timestamp as i64, 0)
+                        .map(|t| t.to_rfc3339())
+                        .unwrap_or_default();
+                    Some(BackupInfo {
+                        path: entry.path().to_string_lossy().to_string(),
+                        timestamp,
+                        created_at,
+                        size_bytes: metadata.len(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+    backups
+}
+
+/// 只保留最新的 `keep` 个备份，删除其余的
+fn rotate_config_backups(config_path: &std::path::Path, keep: usize) {
+    for backup in list_config_backups(config_path).into_iter().skip(keep) {
+        match fs::remove_file(&backup.path) {
+            Ok(_) => info!("Removed old Claude config backup: {}", backup.path),
+            Err(e) => error!("Failed to remove old backup {}: {}", backup.path, e),
+        }
+    }
+}
+
+/// 备份Claude配置文件，并只保留最新的 `keep` 个备份（默认 10 个）
 #[tauri::command]
-pub async fn mcp_backup_claude_global_config() -> Result<String, String> {
+pub async fn mcp_backup_claude_global_config(keep: Option<usize>) -> Result<String, String> {
     info!("Backing up Claude global config");
     
     let config_paths = get_claude_config_paths();
+    let keep = keep.unwrap_or(DEFAULT_CONFIG_BACKUPS_KEPT).max(1);
     
     for config_path in &config_paths {
         if config_path.exists() {
@@ -164,6 +471,7 @@ pub async fn mcp_backup_claude_global_config() -> Result<String, String> {
             match fs::copy(config_path, &backup_path) {
                 Ok(_) => {
                     info!("Backup created at: {:?}", backup_path);
+                    rotate_config_backups(config_path, keep);
                     return Ok(format!("Backup created: {}", backup_path.display()));
                 }
                 Err(e) => {
@@ -177,6 +485,53 @@ pub async fn mcp_backup_claude_global_config() -> Result<String, String> {
     Err("No Claude config file found to backup".to_string())
 }
 
+/// 列出Claude配置文件的可用备份，最新的在前
+#[tauri::command]
+pub async fn mcp_list_claude_config_backups() -> Result<Vec<BackupInfo>, String> {
+    let mut backups: Vec<BackupInfo> = get_claude_config_paths()
+        .iter()
+        .flat_map(|path| list_config_backups(path))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+    backups.dedup_by(|a, b| a.path == b.path);
+    Ok(backups)
+}
+
+/// 从备份恢复Claude配置文件
+/// 备份必须是有效的 JSON 且包含 `mcpServers`；恢复前会先备份当前配置
+#[tauri::command]
+pub async fn mcp_restore_claude_global_config(backup_path: String) -> Result<String, String> {
+    info!("Restoring Claude global config from: {}", backup_path);
+
+    // 只接受由备份命令生成的文件
+    let config_path = get_claude_config_paths()
+        .into_iter()
+        .find(|path| list_config_backups(path).iter().any(|b| b.path == backup_path))
+        .ok_or_else(|| format!("Not a known Claude config backup: {}", backup_path))?;
+
+    let content = fs::read_to_string(&backup_path)
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    let config: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Backup is not valid JSON: {}", e))?;
+    if config.get("mcpServers").is_none() {
+        return Err("Backup does not contain an mcpServers section".to_string());
+    }
+
+    if config_path.exists() {
+        mcp_backup_claude_global_config(None).await?;
+    }
+
+    // 先写临时文件再替换，避免写到一半留下损坏的配置
+    let temp_path = config_path.with_extension("json.restore.tmp");
+    fs::write(&temp_path, &content)
+        .map_err(|e| format!("Failed to write restored config: {}", e))?;
+    fs::rename(&temp_path, &config_path)
+        .map_err(|e| format!("Failed to replace Claude config: {}", e))?;
+
+    info!("Restored Claude config {:?} from {}", config_path, backup_path);
+    Ok(format!("Claude configuration restored from: {}", backup_path))
+}
+
 /// Server status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerStatus {
@@ -221,6 +576,9 @@ pub struct AddServerResult {
 pub struct ImportResult {
     pub imported_count: u32,
     pub failed_count: u32,
+    /// Servers left untouched because they already exist
+    #[serde(default)]
+    pub skipped_count: u32,
     pub servers: Vec<ImportServerResult>,
 }
 
@@ -232,6 +590,51 @@ pub struct ImportServerResult {
     pub error: Option<String>,
 }
 
+/// PATH that claude, and the MCP servers it starts, are spawned with. On macOS a GUI app
+/// doesn't inherit the shell PATH, so Homebrew, ~/.local/bin and the latest NVM node bin
+/// are put in front of it. Also returns the NVM node bin directory when one was added.
+fn augmented_spawn_path() -> (String, Option<PathBuf>) {
+    let current_path = std::env::var("PATH").unwrap_or_default();
+    if !cfg!(target_os = "macos") {
+        return (current_path, None);
+    }
+
+    let mut parts: Vec<String> = current_path.split(':').map(|s| s.to_string()).collect();
+    let mut add_path = |p: String| {
+        if !p.is_empty() && PathBuf::from(&p).exists() && !parts.iter().any(|x| x == &p) {
+            parts.insert(0, p);
+        }
+    };
+
+    // Homebrew typical locations
+    add_path("/opt/homebrew/bin".to_string());
+    add_path("/usr/local/bin".to_string());
+
+    let mut nvm_bin = None;
+    if let Ok(home) = std::env::var("HOME") {
+        add_path(format!("{}/.local/bin", home));
+        add_path(format!("{}/bin", home));
+
+        // Detect latest NVM Node bin
+        let nvm_versions = PathBuf::from(&home).join(".nvm").join("versions").join("node");
+        if let Ok(entries) = fs::read_dir(&nvm_versions) {
+            let mut version_dirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+            version_dirs.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+            if let Some(latest) = version_dirs.first() {
+                let bin = latest.join("bin");
+                add_path(bin.to_string_lossy().to_string());
+                nvm_bin = Some(bin);
+            }
+        }
+    }
+
+    // System fallbacks
+    add_path("/usr/bin".to_string());
+    add_path("/bin".to_string());
+
+    (parts.join(":"), nvm_bin)
+}
+
 /// Executes a claude mcp command
 async fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result<String> {
     info!("Executing claude mcp command with args: {:?}", args);
@@ -279,48 +682,13 @@ async fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) ->
         // macOS-specific: augment PATH to include common Node/Homebrew/NVM locations
         #[cfg(target_os = "macos")]
         {
-            use std::fs;
-            use std::path::PathBuf;
-
-            let current_path = std::env::var("PATH").unwrap_or_default();
-            let mut parts: Vec<String> = current_path.split(':').map(|s| s.to_string()).collect();
-            let mut add_path = |p: String| {
-                if !p.is_empty() && PathBuf::from(&p).exists() && !parts.iter().any(|x| x == &p) {
-                    parts.insert(0, p);
-                }
-            };
-
-            // Homebrew typical locations
-            add_path("/opt/homebrew/bin".to_string());
-            add_path("/usr/local/bin".to_string());
-
-            if let Ok(home) = std::env::var("HOME") {
-                add_path(format!("{}/.local/bin", home));
-                add_path(format!("{}/bin", home));
-
-                // Detect latest NVM Node bin
-                let nvm_versions = PathBuf::from(&home).join(".nvm").join("versions").join("node");
-                if nvm_versions.exists() {
-                    if let Ok(entries) = fs::read_dir(&nvm_versions) {
-                        let mut version_dirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
-                        version_dirs.sort_by(|a,b| b.file_name().cmp(&a.file_name()));
-                        if let Some(latest) = version_dirs.first() {
-                            let bin = latest.join("bin");
-                            add_path(bin.to_string_lossy().to_string());
-                            // Also set NVM_DIR/NVM_BIN if not present
-                            let nvm_dir = PathBuf::from(&home).join(".nvm");
-                            sidecar_cmd = sidecar_cmd.env("NVM_DIR", nvm_dir.to_string_lossy().to_string());
-                            sidecar_cmd = sidecar_cmd.env("NVM_BIN", latest.join("bin").to_string_lossy().to_string());
-                        }
-                    }
-                }
+            let (new_path, nvm_bin) = augmented_spawn_path();
+            if let (Some(nvm_bin), Ok(home)) = (nvm_bin, std::env::var("HOME")) {
+                // Also set NVM_DIR/NVM_BIN if not present
+                let nvm_dir = PathBuf::from(&home).join(".nvm");
+                sidecar_cmd = sidecar_cmd.env("NVM_DIR", nvm_dir.to_string_lossy().to_string());
+                sidecar_cmd = sidecar_cmd.env("NVM_BIN", nvm_bin.to_string_lossy().to_string());
             }
-
-            // System fallbacks
-            add_path("/usr/bin".to_string());
-            add_path("/bin".to_string());
-
-            let new_path = parts.join(":");
             sidecar_cmd = sidecar_cmd.env("PATH", new_path);
         }
 
@@ -497,7 +865,11 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
             // Check if no servers are configured
             if trimmed.contains("No MCP servers configured") || trimmed.is_empty() {
                 info!("No servers found - empty or 'No MCP servers' message");
-                return Ok(vec![]);
+                let current_project_path = std::env::current_dir()
+                    .unwrap_or_else(|_| PathBuf::from("."))
+                    .to_string_lossy()
+                    .to_string();
+                return Ok(parked_server_entries(&read_parked_servers(&app).await, &current_project_path));
             }
 
             // Read project .mcp.json config to get disabled status
@@ -506,10 +878,19 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
                 .to_string_lossy()
                 .to_string();
             
-            let project_config = mcp_read_project_config(current_project_path).await.unwrap_or_else(|_| MCPProjectConfig {
+            let project_config = mcp_read_project_config(current_project_path.clone()).await.unwrap_or_else(|_| MCPProjectConfig {
                 mcp_servers: HashMap::new(),
             });
 
+            // CLI 记录的被拒绝的 .mcp.json 服务器
+            let global_config: serde_json::Value = mcp_read_claude_global_config()
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+            let parked = read_parked_servers(&app).await;
+            let scope_disabled = scope_disabled_servers(&global_config, &parked, "project", &current_project_path);
+
             // Parse the text output, handling multi-line commands
             let mut servers = Vec::new();
             let lines: Vec<&str> = trimmed.lines().collect();
@@ -570,11 +951,12 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
                         let full_command = command_parts.join(" ");
                         info!("Full command for server '{}': {:?}", name, full_command);
 
-                        // Check if server is disabled in project config
+                        // Check if server is disabled in project config or rejected in ~/.claude.json
                         let disabled = project_config.mcp_servers
                             .get(&name)
                             .map(|config| config.disabled)
-                            .unwrap_or(false);
+                            .unwrap_or(false)
+                            || scope_disabled.contains(&name);
                         
                         info!("Server '{}' disabled status from config: {}", name, disabled);
 
@@ -588,7 +970,7 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
                             url: None,
                             scope: "local".to_string(), // Default assumption
                             is_active: false,
-                            disabled, // Read from project config
+                            disabled, // Read from project config and disabledMcpjsonServers
                             status: ServerStatus {
                                 running: false,
                                 error: None,
@@ -608,6 +990,13 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
                 i += 1;
             }
 
+            // 被禁用的 user/local 服务器已从 CLI 配置中移出，从暂存区补充
+            for server in parked_server_entries(&parked, &current_project_path) {
+                if !servers.iter().any(|s| s.name == server.name) {
+                    servers.push(server);
+                }
+            }
+
             info!("Found {} MCP servers total", servers.len());
             for (idx, server) in servers.iter().enumerate() {
                 info!(
@@ -778,10 +1167,244 @@ pub async fn mcp_remove_from_scope(app: AppHandle, name: String, scope: String)
     }
 }
 
+/// Reads every server's raw configuration from one scope, keyed by name
+async fn read_scope_servers(
+    scope: &str,
+    project_path: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let config: serde_json::Value = match scope {
+        "user" | "local" => {
+            let content = mcp_read_claude_global_config().await?;
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse Claude config: {}", e))?
+        }
+        "project" => {
+            let mcp_json_path = PathBuf::from(project_path).join(".mcp.json");
+            let content = fs::read_to_string(&mcp_json_path)
+                .map_err(|e| format!("Failed to read .mcp.json: {}", e))?;
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse .mcp.json: {}", e))?
+        }
+        other => return Err(format!("Invalid scope: {}. Expected user, local or project", other)),
+    };
+
+    let servers = match scope {
+        "local" => config.get("projects").and_then(|p| p.get(project_path)),
+        _ => Some(&config),
+    }
+    .and_then(|c| c.get("mcpServers"))
+    .and_then(|s| s.as_object())
+    .cloned()
+    .unwrap_or_default();
+    Ok(servers)
+}
+
+/// Reads a server's raw configuration from one scope, keeping every field (type, url, headers, env...)
+async fn read_scope_server_config(scope: &str, name: &str, project_path: &str) -> Result<serde_json::Value, String> {
+    read_scope_servers(scope, project_path)
+        .await?
+        .remove(name)
+        .ok_or_else(|| format!("Server '{}' not found in {} scope", name, scope))
+}
+
+/// Moves an MCP server between scopes with its full configuration and disabled state.
+/// The server is only removed from the source scope after it has been added to, and read
+/// back from, the target scope. Local and project scopes refer to the current directory.
+#[tauri::command]
+pub async fn mcp_move_server(
+    app: AppHandle,
+    name: String,
+    from_scope: String,
+    to_scope: String,
+) -> Result<String, String> {
+    info!("Moving MCP server '{}' from {} to {} scope", name, from_scope, to_scope);
+
+    if from_scope == to_scope {
+        return Err(format!("Server '{}' is already in {} scope", name, to_scope));
+    }
+
+    let project_path = std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .to_string_lossy()
+        .to_string();
+
+    // Disabled user/local servers are parked outside ~/.claude.json
+    let parked = read_parked_servers(&app).await;
+    let parked_config = match from_scope.as_str() {
+        "user" | "local" => parked_scope_servers(&parked, &from_scope, &project_path).remove(&name),
+        _ => None,
+    };
+    let source_parked = parked_config.is_some();
+    let mut server_config = match parked_config {
+        Some(config) => config,
+        None => read_scope_server_config(&from_scope, &name, &project_path).await?,
+    };
+
+    // Project servers count as disabled when either .mcp.json or the CLI's choice says so
+    let rejected_by_cli = if from_scope == "project" {
+        let global: serde_json::Value = mcp_read_claude_global_config()
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        scope_disabled_servers(&global, &parked, "project", &project_path).contains(&name)
+    } else {
+        false
+    };
+    let disabled = source_parked
+        || rejected_by_cli
+        || server_config.get("disabled").and_then(|d| d.as_bool()) == Some(true);
+
+    // .mcp.json tracks disabled state inline; the CLI schema doesn't accept it
+    if let Some(obj) = server_config.as_object_mut() {
+        obj.remove("disabled");
+    }
+
+    let added = mcp_add_json(app.clone(), name.clone(), server_config.to_string(), to_scope.clone()).await?;
+    if !added.success {
+        error!("Failed to add '{}' to {} scope, source left untouched", name, to_scope);
+        return Err(format!("Failed to add server to {} scope: {}", to_scope, added.message));
+    }
+
+    if let Err(e) = read_scope_server_config(&to_scope, &name, &project_path).await {
+        error!("Server '{}' missing from {} scope after add: {}", name, to_scope, e);
+        return Err(format!(
+            "Server was not found in {} scope after adding it; source left untouched: {}",
+            to_scope, e
+        ));
+    }
+
+    if disabled {
+        if let Err(e) = mcp_toggle_disabled(
+            app.clone(),
+            name.clone(),
+            true,
+            Some(project_path.clone()),
+            Some(to_scope.clone()),
+        )
+        .await
+        {
+            error!("Failed to disable '{}' in {} scope: {}", name, to_scope, e);
+            return Err(format!(
+                "Server was added to {} scope but could not be disabled there; source left untouched: {}",
+                to_scope, e
+            ));
+        }
+    }
+
+    let removed = if source_parked {
+        update_parked_server(&app, &from_scope, &project_path, &name, None)
+            .await
+            .map(|_| String::new())
+    } else {
+        mcp_remove_from_scope(app, name.clone(), from_scope.clone()).await
+    };
+    match removed {
+        Ok(_) => {
+            info!("Moved MCP server '{}' from {} to {} scope", name, from_scope, to_scope);
+            Ok(format!("Moved server '{}' from {} to {} scope", name, from_scope, to_scope))
+        }
+        Err(e) => Err(format!(
+            "Server '{}' was added to {} scope but could not be removed from {} scope: {}",
+            name, to_scope, from_scope, e
+        )),
+    }
+}
+
+/// Builds a minimal .mcp.json entry for a server known to the CLI but missing from the file
+async fn server_config_from_cli(app: &AppHandle, name: &str, disabled: bool) -> Result<MCPServerConfig> {
+    let output = execute_claude_mcp_command(app, vec!["get", name]).await?;
+
+    // Parse the command from output
+    let mut command = String::new();
+    let mut args = Vec::new();
+    let env = HashMap::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("Command:") {
+            let full_command = line.replace("Command:", "").trim().to_string();
+            let parts: Vec<&str> = full_command.split_whitespace().collect();
+            if !parts.is_empty() {
+                command = parts[0].to_string();
+                args = parts[1..].iter().map(|s| s.to_string()).collect();
+            }
+        }
+        // TODO: Parse environment variables if needed
+    }
+
+    Ok(MCPServerConfig {
+        command,
+        args,
+        env,
+        disabled,
+    })
+}
+
+/// Outcome of a batch enable/disable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPBatchToggleResult {
+    pub updated: Vec<String>,
+    pub not_found: Vec<String>,
+}
+
+/// Sets the disabled flag on several project-scope servers, reading and writing .mcp.json once.
+/// Servers missing from the file are added from `claude mcp get`, like the single toggle does;
+/// names the CLI doesn't know either are reported as not found.
+#[tauri::command]
+pub async fn mcp_set_servers_disabled(
+    app: AppHandle,
+    project_path: String,
+    names: Vec<String>,
+    disabled: bool,
+) -> Result<MCPBatchToggleResult, String> {
+    info!("Setting disabled={} on {} MCP servers in {}", disabled, names.len(), project_path);
+
+    let mut config = mcp_read_project_config(project_path.clone()).await?;
+    let mut result = MCPBatchToggleResult {
+        updated: Vec::new(),
+        not_found: Vec::new(),
+    };
+
+    for name in names {
+        if result.updated.contains(&name) || result.not_found.contains(&name) {
+            continue;
+        }
+        if let Some(server_config) = config.mcp_servers.get_mut(&name) {
+            server_config.disabled = disabled;
+            result.updated.push(name);
+            continue;
+        }
+        match server_config_from_cli(&app, &name, disabled).await {
+            Ok(server_config) => {
+                info!("Created new config entry for server '{}'", name);
+                config.mcp_servers.insert(name.clone(), server_config);
+                result.updated.push(name);
+            }
+            Err(e) => {
+                info!("Server '{}' not found: {}", name, e);
+                result.not_found.push(name);
+            }
+        }
+    }
+
+    if !result.updated.is_empty() {
+        mcp_save_project_config(project_path.clone(), config).await?;
+        sync_project_mcpjson_choice(&project_path, &result.updated, disabled).await?;
+    }
+
+    Ok(result)
+}
+
 /// Toggles the disabled status of an MCP server
 #[tauri::command]
-pub async fn mcp_toggle_disabled(app: AppHandle, name: String, disabled: bool, project_path: Option<String>) -> Result<String, String> {
-    info!("Toggling MCP server '{}' disabled status to: {}", name, disabled);
+pub async fn mcp_toggle_disabled(
+    app: AppHandle,
+    name: String,
+    disabled: bool,
+    project_path: Option<String>,
+    scope: Option<String>,
+) -> Result<String, String> {
+    let scope = scope.unwrap_or_else(|| "project".to_string());
+    info!("Toggling MCP server '{}' disabled status to: {} (scope: {})", name, disabled, scope);
     
     // For now, we'll use the current working directory as the project path if not provided
     let current_project_path = project_path.unwrap_or_else(|| {
@@ -791,6 +1414,19 @@ pub async fn mcp_toggle_disabled(app: AppHandle, name: String, disabled: bool, p
             .to_string()
     });
     
+    // user/local 作用域没有 .mcp.json，也没有 CLI 禁用开关：禁用即从 ~/.claude.json 移出并暂存
+    match scope.as_str() {
+        "user" | "local" => {
+            set_scope_server_disabled(&app, &scope, &current_project_path, &name, disabled).await?;
+
+            let status = if disabled { "disabled" } else { "enabled" };
+            info!("Successfully {} MCP server '{}' at {} scope", status, name, scope);
+            return Ok(format!("Server '{}' has been {}", name, status));
+        }
+        "project" => {}
+        other => return Err(format!("Invalid scope: {}. Expected user, local or project", other)),
+    }
+    
     // Read the current .mcp.json configuration
     match mcp_read_project_config(current_project_path.clone()).await {
         Ok(mut config) => {
@@ -801,33 +1437,10 @@ pub async fn mcp_toggle_disabled(app: AppHandle, name: String, disabled: bool, p
                 info!("Server '{}' not found in .mcp.json, attempting to create config entry", name);
                 
                 // Get server details using claude mcp get command
-                match execute_claude_mcp_command(&app, vec!["get", &name]).await {
-                    Ok(output) => {
-                        // Parse the command from output
-                        let mut command = String::new();
-                        let mut args = Vec::new();
-                        let env = HashMap::new();
-                        
-                        for line in output.lines() {
-                            let line = line.trim();
-                            if line.starts_with("Command:") {
-                                let full_command = line.replace("Command:", "").trim().to_string();
-                                let parts: Vec<&str> = full_command.split_whitespace().collect();
-                                if !parts.is_empty() {
-                                    command = parts[0].to_string();
-                                    args = parts[1..].iter().map(|s| s.to_string()).collect();
-                                }
-                            }
-                            // TODO: Parse environment variables if needed
-                        }
-                        
+                match server_config_from_cli(&app, &name, disabled).await {
+                    Ok(server_config) => {
                         // Create new server config entry
-                        config.mcp_servers.insert(name.clone(), MCPServerConfig {
-                            command,
-                            args,
-                            env,
-                            disabled,
-                        });
+                        config.mcp_servers.insert(name.clone(), server_config);
                         
                         info!("Created new config entry for server '{}'", name);
                     }
@@ -846,8 +1459,10 @@ pub async fn mcp_toggle_disabled(app: AppHandle, name: String, disabled: bool, p
             }
             
             // Save the updated configuration
-            match mcp_save_project_config(current_project_path, config).await {
+            match mcp_save_project_config(current_project_path.clone(), config).await {
                 Ok(_) => {
+                    // .mcp.json 中的 disabled 仅供界面显示，CLI 读取的是 disabledMcpjsonServers
+                    sync_project_mcpjson_choice(&current_project_path, std::slice::from_ref(&name), disabled).await?;
                     let status = if disabled { "disabled" } else { "enabled" };
                     info!("Successfully {} MCP server: {}", status, name);
                     Ok(format!("Server '{}' has been {}", name, status))
@@ -906,15 +1521,19 @@ pub async fn mcp_add_json(
     }
 }
 
-/// Imports MCP servers from Claude Desktop
+/// Imports MCP servers from Claude Desktop.
+/// Servers that already exist are skipped unless `overwrite` is set, in which case
+/// they are removed from the target scope and re-added, so re-running is idempotent.
 #[tauri::command]
 pub async fn mcp_add_from_claude_desktop(
     app: AppHandle,
     scope: String,
+    overwrite: Option<bool>,
 ) -> Result<ImportResult, String> {
+    let overwrite = overwrite.unwrap_or(false);
     info!(
-        "Importing MCP servers from Claude Desktop with scope: {}",
-        scope
+        "Importing MCP servers from Claude Desktop with scope: {} (overwrite: {})",
+        scope, overwrite
     );
 
     // Get Claude Desktop config path based on platform
@@ -958,8 +1577,17 @@ pub async fn mcp_add_from_claude_desktop(
         .and_then(|v| v.as_object())
         .ok_or_else(|| "No MCP servers found in Claude Desktop config".to_string())?;
 
+    let existing: std::collections::HashSet<String> = match mcp_list(app.clone()).await {
+        Ok(servers) => servers.into_iter().map(|s| s.name).collect(),
+        Err(e) => {
+            warn!("Could not list existing MCP servers, importing without dedup: {}", e);
+            std::collections::HashSet::new()
+        }
+    };
+
     let mut imported_count = 0;
     let mut failed_count = 0;
+    let mut skipped_count = 0;
     let mut server_results = Vec::new();
 
     // Import each server using add-json
@@ -1012,6 +1640,29 @@ pub async fn mcp_add_from_claude_desktop(
         let json_str = serde_json::to_string(&json_config)
             .map_err(|e| format!("Failed to serialize config for {}: {}", name, e))?;
 
+        if existing.contains(name) {
+            if !overwrite {
+                skipped_count += 1;
+                server_results.push(ImportServerResult {
+                    name: name.clone(),
+                    success: false,
+                    error: Some("skipped: already exists".to_string()),
+                });
+                info!("Skipping existing server: {}", name);
+                continue;
+            }
+            if let Err(e) = mcp_remove_from_scope(app.clone(), name.clone(), scope.clone()).await {
+                failed_count += 1;
+                server_results.push(ImportServerResult {
+                    name: name.clone(),
+                    success: false,
+                    error: Some(format!("Failed to remove existing server before overwrite: {}", e)),
+                });
+                error!("Failed to remove existing server {} for overwrite: {}", name, e);
+                continue;
+            }
+        }
+
         // Call add-json command
         match mcp_add_json(app.clone(), name.clone(), json_str, scope.clone()).await {
             Ok(result) => {
@@ -1048,13 +1699,14 @@ pub async fn mcp_add_from_claude_desktop(
     }
 
     info!(
-        "Import complete: {} imported, {} failed",
-        imported_count, failed_count
+        "Import complete: {} imported, {} skipped, {} failed",
+        imported_count, skipped_count, failed_count
     );
 
     Ok(ImportResult {
         imported_count,
         failed_count,
+        skipped_count,
         servers: server_results,
     })
 }
@@ -1316,6 +1968,36 @@ pub async fn mcp_debug_claude_info(app: AppHandle) -> Result<serde_json::Value,
     Ok(serde_json::Value::Object(debug_info))
 }
 
+/// Writes the `mcp_debug_claude_info` output to `file_path` as pretty-printed JSON,
+/// together with the export time and app version. Returns the written path.
+#[tauri::command]
+pub async fn export_mcp_debug_info(app: AppHandle, file_path: String) -> Result<String, String> {
+    let path = PathBuf::from(file_path.trim());
+    if path.as_os_str().is_empty() {
+        return Err("File path cannot be empty".to_string());
+    }
+
+    let debug_info = mcp_debug_claude_info(app.clone()).await?;
+    let document = serde_json::json!({
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "debug_info": debug_info,
+    });
+    let content = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize MCP debug info: {}", e))?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    info!("Exported MCP debug info to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
 /// Reads .mcp.json from the current project
 #[tauri::command]
 pub async fn mcp_read_project_config(project_path: String) -> Result<MCPProjectConfig, String> {
@@ -1370,3 +2052,723 @@ pub async fn mcp_get_scope_priority() -> Result<String, String> {
     // Return scope priority as comma-separated string: user,project,local
     Ok("user,project,local".to_string())
 }
+
+/// Severity of a .mcp.json lint issue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem found while validating .mcp.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPConfigIssue {
+    /// Server the issue applies to, or None for file-level issues
+    pub server: Option<String>,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// JSON object entries in document order, keeping duplicate keys
+struct OrderedEntries(Vec<(String, serde_json::Value)>);
+
+impl<'de> Deserialize<'de> for OrderedEntries {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct EntriesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for EntriesVisitor {
+            type Value = OrderedEntries;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, serde_json::Value>()? {
+                    entries.push((key, value));
+                }
+                Ok(OrderedEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// Raw .mcp.json shape used for linting
+#[derive(Deserialize)]
+struct RawProjectConfig {
+    #[serde(rename = "mcpServers")]
+    mcp_servers: Option<OrderedEntries>,
+}
+
+/// Validates .mcp.json beyond parsing: commands resolve, args are strings,
+/// env keys are valid identifiers and server names are unique
+#[tauri::command]
+pub async fn mcp_validate_project_config(project_path: String) -> Result<Vec<MCPConfigIssue>, String> {
+    info!("Validating .mcp.json in project: {}", project_path);
+
+    let mcp_json_path = PathBuf::from(&project_path).join(".mcp.json");
+    if !mcp_json_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&mcp_json_path)
+        .map_err(|e| format!("Failed to read .mcp.json: {}", e))?;
+
+    let mut issues = Vec::new();
+    let file_issue = |message: String| MCPConfigIssue {
+        server: None,
+        severity: IssueSeverity::Error,
+        message,
+    };
+
+    let raw: RawProjectConfig = match serde_json::from_str(&content) {
+        Ok(raw) => raw,
+        Err(e) => {
+            issues.push(file_issue(format!("Invalid JSON: {}", e)));
+            return Ok(issues);
+        }
+    };
+
+    let servers = match raw.mcp_servers {
+        Some(servers) => servers.0,
+        None => {
+            issues.push(MCPConfigIssue {
+                server: None,
+                severity: IssueSeverity::Warning,
+                message: "Missing \"mcpServers\" object".to_string(),
+            });
+            return Ok(issues);
+        }
+    };
+
+    let env_key_regex = regex::Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").map_err(|e| e.to_string())?;
+    let mut seen = std::collections::HashSet::new();
+
+    for (name, server) in &servers {
+        let mut push = |severity: IssueSeverity, message: String| {
+            issues.push(MCPConfigIssue {
+                server: Some(name.clone()),
+                severity,
+                message,
+            })
+        };
+
+        if !seen.insert(name.clone()) {
+            push(IssueSeverity::Error, format!("Duplicate server name '{}'", name));
+        }
+
+        let server = match server.as_object() {
+            Some(server) => server,
+            None => {
+                push(IssueSeverity::Error, "Server configuration must be an object".to_string());
+                continue;
+            }
+        };
+
+        // Remote (sse/http) servers use a url instead of a command
+        if server.get("command").is_none() && server.get("url").is_some() {
+            continue;
+        }
+
+        match server.get("command").and_then(|c| c.as_str()) {
+            None => push(IssueSeverity::Error, "Missing \"command\" string".to_string()),
+            Some(command) if command.trim().is_empty() => {
+                push(IssueSeverity::Error, "\"command\" is empty".to_string())
+            }
+            Some(command) => {
+                let command_path = PathBuf::from(command);
+                let resolved = if command_path.is_absolute() {
+                    command_path.is_file()
+                } else if command.contains('/') || command.contains('\\') {
+                    PathBuf::from(&project_path).join(&command_path).is_file()
+                } else {
+                    // Resolve the way the server will actually be started
+                    let (search_path, _) = augmented_spawn_path();
+                    which::which_in(command, Some(search_path), &project_path).is_ok()
+                };
+                if !resolved {
+                    push(
+                        IssueSeverity::Error,
+                        format!("Command '{}' was not found on PATH or on disk", command),
+                    );
+                }
+            }
+        }
+
+        match server.get("args") {
+            None => {}
+            Some(serde_json::Value::Array(args)) => {
+                for (index, arg) in args.iter().enumerate() {
+                    if !arg.is_string() {
+                        push(
+                            IssueSeverity::Error,
+                            format!("Argument {} is not a string: {}", index, arg),
+                        );
+                    }
+                }
+            }
+            Some(_) => push(IssueSeverity::Error, "\"args\" must be an array of strings".to_string()),
+        }
+
+        match server.get("env") {
+            None => {}
+            Some(serde_json::Value::Object(env)) => {
+                for (key, value) in env {
+                    if !env_key_regex.is_match(key) {
+                        push(
+                            IssueSeverity::Error,
+                            format!("Invalid environment variable name '{}'", key),
+                        );
+                    }
+                    if !value.is_string() {
+                        push(
+                            IssueSeverity::Warning,
+                            format!("Environment variable '{}' should be a string", key),
+                        );
+                    }
+                }
+            }
+            Some(_) => push(IssueSeverity::Error, "\"env\" must be an object".to_string()),
+        }
+    }
+
+    info!("Found {} issues in .mcp.json", issues.len());
+    Ok(issues)
+}
+
+/// Upper bound on captured stdout/stderr per probe
+const PROBE_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// Outcome of launching a stdio MCP server and sending `initialize`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPProbeResult {
+    /// True once the server answered `initialize` with a result
+    pub success: bool,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub capabilities: Option<serde_json::Value>,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// Append to a capture buffer, dropping anything past the limit
+fn push_probe_output(buffer: &mut String, text: &str) {
+    let remaining = PROBE_OUTPUT_LIMIT.saturating_sub(buffer.len());
+    if remaining == 0 {
+        return;
+    }
+    let mut end = text.len().min(remaining);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    buffer.push_str(&text[..end]);
+}
+
+/// Launches a stdio MCP server, sends an `initialize` request and reports what it answered.
+/// The process is killed once it responds or the timeout elapses; nothing is added to any config.
+#[tauri::command]
+pub async fn mcp_probe_stdio(
+    command: String,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    timeout_secs: Option<u64>,
+) -> Result<MCPProbeResult, String> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+    info!("Probing stdio MCP server: {} {:?}", command, args);
+
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(10).clamp(1, 120));
+    let started = std::time::Instant::now();
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    let mut std_cmd = create_command_with_env(&command);
+    std_cmd
+        .args(&args)
+        .envs(&env)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    let mut cmd = tokio::process::Command::from(std_cmd);
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+
+    let mut stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let stderr_task = tokio::spawn(async move {
+        let mut bytes = Vec::new();
+        let _ = stderr.read_to_end(&mut bytes).await;
+        String::from_utf8_lossy(&bytes).to_string()
+    });
+
+    let mut result = MCPProbeResult {
+        success: false,
+        server_name: None,
+        server_version: None,
+        protocol_version: None,
+        capabilities: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+        elapsed_ms: 0,
+    };
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": "termiclaude", "version": env!("CARGO_PKG_VERSION") }
+        }
+    });
+    let mut stdin = child.stdin.take().ok_or("Failed to capture stdin")?;
+    if let Err(e) = stdin
+        .write_all(format!("{}\n", initialize).as_bytes())
+        .await
+    {
+        result.error = Some(format!("Failed to send initialize request: {}", e));
+    }
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let mut lines = tokio::io::BufReader::new(stdout).lines();
+    while result.error.is_none() {
+        let line = match tokio::time::timeout_at(deadline, lines.next_line()).await {
+            Ok(Ok(Some(line))) => line,
+            Ok(Ok(None)) => {
+                let status = child.try_wait().ok().flatten();
+                result.error = Some(match status {
+                    Some(status) => format!("Server exited before responding ({})", status),
+                    None => "Server closed stdout before responding".to_string(),
+                });
+                break;
+            }
+            Ok(Err(e)) => {
+                result.error = Some(format!("Failed to read server output: {}", e));
+                break;
+            }
+            Err(_) => {
+                result.error = Some(format!(
+                    "No initialize response within {}s",
+                    timeout.as_secs()
+                ));
+                break;
+            }
+        };
+        push_probe_output(&mut result.stdout, &line);
+        push_probe_output(&mut result.stdout, "\n");
+
+        // Servers may log to stdout; only the response to our request matters
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+        if message.get("id") != Some(&serde_json::json!(1)) {
+            continue;
+        }
+        if let Some(error) = message.get("error") {
+            result.error = Some(format!("Server rejected initialize: {}", error));
+            break;
+        }
+        if let Some(init) = message.get("result") {
+            result.success = true;
+            result.server_name = init
+                .pointer("/serverInfo/name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            result.server_version = init
+                .pointer("/serverInfo/version")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            result.protocol_version = init
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            result.capabilities = init.get("capabilities").cloned();
+            break;
+        }
+    }
+
+    drop(stdin);
+    let _ = child.kill().await;
+    if let Ok(Ok(stderr)) =
+        tokio::time::timeout(std::time::Duration::from_secs(2), stderr_task).await
+    {
+        push_probe_output(&mut result.stderr, &stderr);
+    }
+    result.elapsed_ms = started.elapsed().as_millis() as u64;
+
+    if result.success {
+        info!(
+            "MCP probe succeeded: {} {}",
+            result.server_name.as_deref().unwrap_or("<unnamed>"),
+            result.server_version.as_deref().unwrap_or("")
+        );
+    } else {
+        error!("MCP probe failed: {}", result.error.as_deref().unwrap_or("unknown error"));
+    }
+    Ok(result)
+}
+
+/// Running diagnostic log captures keyed by server name, each tagged with a capture id
+/// so a finished capture never removes the one that replaced it
+#[derive(Default)]
+pub struct MCPLogCaptureState(std::sync::Mutex<HashMap<String, (u64, tokio::task::JoinHandle<()>)>>);
+
+/// Source of capture ids for `MCPLogCaptureState`
+static NEXT_LOG_CAPTURE_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// A line of output from a captured server, emitted as `mcp-log:{name}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPLogLine {
+    /// "stdout", "stderr" or "exit"
+    pub stream: String,
+    pub line: String,
+}
+
+/// Look up a server's configuration the way Claude resolves it: local, then project, then user
+async fn resolve_server_config(name: &str, project_path: &str) -> Result<(String, serde_json::Value), String> {
+    for scope in ["local", "project", "user"] {
+        if let Ok(config) = read_scope_server_config(scope, name, project_path).await {
+            return Ok((scope.to_string(), config));
+        }
+    }
+    Err(format!("MCP server '{}' not found in any scope", name))
+}
+
+/// Start a separate instance of a stdio server and stream its stdout/stderr as
+/// `mcp-log:{name}` events. This is a diagnostic harness, independent of the server
+/// instance a Claude session uses. Restarting replaces any existing capture.
+#[tauri::command]
+pub async fn mcp_start_log_capture(
+    app: AppHandle,
+    state: tauri::State<'_, MCPLogCaptureState>,
+    name: String,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    use tauri::{Emitter, Manager};
+    use tokio::io::AsyncBufReadExt;
+
+    let project_path = project_path.unwrap_or_else(|| {
+        std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .to_string_lossy()
+            .to_string()
+    });
+    let (scope, config) = resolve_server_config(&name, &project_path).await?;
+
+    let transport = config.get("type").and_then(|t| t.as_str()).unwrap_or("stdio");
+    let command = match config.get("command").and_then(|c| c.as_str()) {
+        Some(command) if transport == "stdio" => command.to_string(),
+        _ => return Err(format!("MCP server '{}' is not a stdio server ({})", name, transport)),
+    };
+    let args: Vec<String> = config
+        .get("args")
+        .and_then(|a| a.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+    let env: HashMap<String, String> = config
+        .get("env")
+        .and_then(|e| e.as_object())
+        .map(|e| {
+            e.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut std_cmd = create_command_with_env(&command);
+    std_cmd
+        .args(&args)
+        .envs(&env)
+        .current_dir(&project_path)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    let mut cmd = tokio::process::Command::from(std_cmd);
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    info!("Capturing logs of MCP server '{}' ({} scope): {} {:?}", name, scope, command, args);
+
+    let event = format!("mcp-log:{}", name);
+    let task_name = name.clone();
+    let task_app = app.clone();
+    let capture_id = NEXT_LOG_CAPTURE_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    // The task waits until it is registered, so it can't finish and clean up before that
+    let (registered_tx, registered_rx) = tokio::sync::oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
+        let _ = registered_rx.await;
+        // Keep stdin open so servers that exit on EOF stay up
+        let _stdin = child.stdin.take();
+        let mut stdout = tokio::io::BufReader::new(stdout).lines();
+        let mut stderr = tokio::io::BufReader::new(stderr).lines();
+        let emit = |stream: &str, line: String| {
+            let _ = task_app.emit(&event, MCPLogLine { stream: stream.to_string(), line });
+        };
+
+        let (mut stdout_open, mut stderr_open) = (true, true);
+        while stdout_open || stderr_open {
+            tokio::select! {
+                line = stdout.next_line(), if stdout_open => match line {
+                    Ok(Some(line)) => emit("stdout", line),
+                    _ => stdout_open = false,
+                },
+                line = stderr.next_line(), if stderr_open => match line {
+                    Ok(Some(line)) => emit("stderr", line),
+                    _ => stderr_open = false,
+                },
+            }
+        }
+
+        let status = match child.wait().await {
+            Ok(status) => status.to_string(),
+            Err(e) => e.to_string(),
+        };
+        info!("MCP server '{}' log capture ended: {}", task_name, status);
+        emit("exit", status);
+        if let Ok(mut captures) = task_app.state::<MCPLogCaptureState>().0.lock() {
+            // Only remove our own entry; a restart may already have replaced it
+            if captures.get(&task_name).is_some_and(|(id, _)| *id == capture_id) {
+                captures.remove(&task_name);
+            }
+        }
+    });
+
+    let previous = match state.0.lock() {
+        Ok(mut captures) => captures.insert(name, (capture_id, task)),
+        Err(e) => {
+            task.abort();
+            return Err(e.to_string());
+        }
+    };
+    let _ = registered_tx.send(());
+    if let Some((_, previous)) = previous {
+        previous.abort();
+    }
+    Ok(())
+}
+
+/// Stop a log capture started with `mcp_start_log_capture`; returns false if none was running
+#[tauri::command]
+pub async fn mcp_stop_log_capture(
+    state: tauri::State<'_, MCPLogCaptureState>,
+    name: String,
+) -> Result<bool, String> {
+    let task = state.0.lock().map_err(|e| e.to_string())?.remove(&name);
+    match task {
+        Some((_, task)) => {
+            // Dropping the task drops the child, which kills the server
+            task.abort();
+            info!("Stopped log capture for MCP server '{}'", name);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Per-server probe timeout used by `mcp_audit_servers`
+const AUDIT_PROBE_TIMEOUT_SECS: u64 = 10;
+/// How many servers `mcp_audit_servers` probes at once
+const AUDIT_MAX_CONCURRENT_PROBES: usize = 8;
+
+/// Health classification of a configured MCP server
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MCPAuditStatus {
+    /// Answered `initialize` (stdio) or an HTTP request (sse/http)
+    Reachable,
+    /// Started or resolved but did not answer in time or answered with an error
+    Unreachable,
+    /// The entry cannot work as written: missing command/url, unknown transport, command not found
+    Misconfigured,
+}
+
+/// One row of the MCP audit report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPAuditEntry {
+    pub name: String,
+    pub scope: String,
+    pub transport: String,
+    pub disabled: bool,
+    pub status: MCPAuditStatus,
+    pub reason: String,
+    pub elapsed_ms: u64,
+}
+
+/// Probe one server configuration and classify the outcome
+async fn audit_server(config: &serde_json::Value) -> (MCPAuditStatus, String) {
+    let string_map = |key: &str| -> HashMap<String, String> {
+        config
+            .get(key)
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let transport = config.get("type").and_then(|t| t.as_str()).unwrap_or("stdio");
+    match transport {
+        "stdio" => {
+            let command = match config.get("command").and_then(|c| c.as_str()) {
+                Some(command) if !command.trim().is_empty() => command.to_string(),
+                _ => return (MCPAuditStatus::Misconfigured, "No command configured".to_string()),
+            };
+            let args: Vec<String> = config
+                .get("args")
+                .and_then(|a| a.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+            match mcp_probe_stdio(command, args, string_map("env"), Some(AUDIT_PROBE_TIMEOUT_SECS)).await {
+                Ok(probe) if probe.success => (
+                    MCPAuditStatus::Reachable,
+                    format!(
+                        "Answered initialize as {} {}",
+                        probe.server_name.as_deref().unwrap_or("<unnamed>"),
+                        probe.server_version.as_deref().unwrap_or("")
+                    )
+                    .trim_end()
+                    .to_string(),
+                ),
+                Ok(probe) => (
+                    MCPAuditStatus::Unreachable,
+                    probe.error.unwrap_or_else(|| "No initialize response".to_string()),
+                ),
+                // The process could not even be started, e.g. the package was uninstalled
+                Err(e) => (MCPAuditStatus::Misconfigured, e),
+            }
+        }
+        "sse" | "http" => {
+            let url = match config.get("url").and_then(|u| u.as_str()) {
+                Some(url) if !url.trim().is_empty() => url.to_string(),
+                _ => return (MCPAuditStatus::Misconfigured, "No url configured".to_string()),
+            };
+            if reqwest::Url::parse(&url).is_err() {
+                return (MCPAuditStatus::Misconfigured, format!("Invalid url: {}", url));
+            }
+            let client = match reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(AUDIT_PROBE_TIMEOUT_SECS))
+                .build()
+            {
+                Ok(client) => client,
+                Err(e) => return (MCPAuditStatus::Unreachable, e.to_string()),
+            };
+            let mut request = client.get(&url);
+            for (key, value) in string_map("headers") {
+                request = request.header(key, value);
+            }
+            match request.send().await {
+                Ok(response) if response.status().is_server_error() => (
+                    MCPAuditStatus::Unreachable,
+                    format!("Server error: HTTP {}", response.status()),
+                ),
+                // Any other answer (including 401/405) means the endpoint is alive
+                Ok(response) => (MCPAuditStatus::Reachable, format!("HTTP {}", response.status())),
+                Err(e) if e.is_timeout() => (
+                    MCPAuditStatus::Unreachable,
+                    format!("No response within {}s", AUDIT_PROBE_TIMEOUT_SECS),
+                ),
+                Err(e) => (MCPAuditStatus::Unreachable, e.to_string()),
+            }
+        }
+        other => (MCPAuditStatus::Misconfigured, format!("Unknown transport type '{}'", other)),
+    }
+}
+
+/// Probe every configured MCP server across local, project and user scopes and classify
+/// each as reachable, unreachable or misconfigured. Probes run concurrently with a bounded
+/// timeout; nothing in the configuration is changed.
+#[tauri::command]
+pub async fn mcp_audit_servers(app: AppHandle, project_path: Option<String>) -> Result<Vec<MCPAuditEntry>, String> {
+    use futures::StreamExt;
+
+    let project_path = project_path.unwrap_or_else(|| {
+        std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .to_string_lossy()
+            .to_string()
+    });
+
+    let global_config: serde_json::Value = mcp_read_claude_global_config()
+        .await
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let parked = read_parked_servers(&app).await;
+
+    let mut servers = Vec::new();
+    for scope in ["local", "project", "user"] {
+        // 被禁用的 user/local 服务器只存在于暂存区
+        for (name, config) in parked_scope_servers(&parked, scope, &project_path) {
+            servers.push((scope.to_string(), name, config, true));
+        }
+        let scope_servers = match read_scope_servers(scope, &project_path).await {
+            Ok(scope_servers) => scope_servers,
+            // A missing .mcp.json just means there are no project servers
+            Err(e) => {
+                if scope != "project" {
+                    warn!("Skipping {} scope in MCP audit: {}", scope, e);
+                }
+                continue;
+            }
+        };
+        let disabled = scope_disabled_servers(&global_config, &parked, scope, &project_path);
+        for (name, config) in scope_servers {
+            let is_disabled = disabled.contains(&name)
+                || config.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false);
+            servers.push((scope.to_string(), name, config, is_disabled));
+        }
+    }
+    info!("Auditing {} MCP servers for {}", servers.len(), project_path);
+
+    let mut entries = futures::stream::iter(servers)
+        .map(|(scope, name, config, disabled)| async move {
+            let started = std::time::Instant::now();
+            let transport = config.get("type").and_then(|t| t.as_str()).unwrap_or("stdio").to_string();
+            // The stdio probe has its own deadline; this also bounds process startup and cleanup
+            let limit = std::time::Duration::from_secs(AUDIT_PROBE_TIMEOUT_SECS + 5);
+            let (status, reason) = match tokio::time::timeout(limit, audit_server(&config)).await {
+                Ok(outcome) => outcome,
+                Err(_) => (
+                    MCPAuditStatus::Unreachable,
+                    format!("No response within {}s", limit.as_secs()),
+                ),
+            };
+            MCPAuditEntry {
+                name,
+                scope,
+                transport,
+                disabled,
+                status,
+                reason,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            }
+        })
+        .buffer_unordered(AUDIT_MAX_CONCURRENT_PROBES)
+        .collect::<Vec<_>>()
+        .await;
+    entries.sort_by(|a, b| a.scope.cmp(&b.scope).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
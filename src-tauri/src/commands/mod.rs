@@ -6,8 +6,14 @@
 /// 
 /// ## Module Structure
 /// 
+/// - `activity` - Unified activity feed across agent runs, sessions and checkpoints
 /// - `agents` - Agent management and execution commands
+/// - `agent_schedule` - Recurring agent runs driven by cron expressions
+/// - `agent_sse` - Streaming agent output as server-sent events
+/// - `agent_watch` - Re-running agents when watched files change
 /// - `claude` - Claude Code integration and session management  
+/// - `diagnostics` - Bug report bundles with secrets redacted
+/// - `logs` - Application log viewing
 /// - `mcp` - Model Context Protocol server management
 /// - `settings_monitor` - Configuration monitoring and conflict detection
 /// - `slash_commands` - Slash command discovery and management
@@ -19,11 +25,17 @@
 /// All commands implement proper input validation and use parameterized queries
 /// for database operations to prevent SQL injection attacks.
 
+pub mod activity;
 pub mod agents;
+pub mod agent_schedule;
+pub mod agent_sse;
+pub mod agent_watch;
 pub mod claude;
+pub mod diagnostics;
 pub mod mcp;
 pub mod settings_monitor;
 pub mod usage;
 pub mod storage;
 pub mod slash_commands;
 pub mod proxy;
+pub mod logs;
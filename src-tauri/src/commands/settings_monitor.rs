@@ -18,6 +18,8 @@ pub struct DetailedConfigStatus {
     pub external_env: std::collections::HashMap<String, String>,
     pub internal_vars: std::collections::HashMap<String, String>,
     pub comparison_details: Vec<ComparisonDetail>,
+    /// 监听器当前生效的配置
+    pub monitor_config: MonitorConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,9 +31,98 @@ pub struct ComparisonDetail {
     pub reason: String,
 }
 
+/// 检测间隔下限，避免过小的值导致忙轮询
+const MIN_CHECK_INTERVAL_MS: u64 = 250;
+const CHECK_INTERVAL_SETTING: &str = "settings_monitor_check_interval_ms";
+const STARTUP_DELAY_SETTING: &str = "settings_monitor_startup_delay_ms";
+/// 本应用最近一次写入 settings.json 后 `env` 部分的哈希
+const LAST_WRITE_HASH_SETTING: &str = "settings_last_write_hash";
+
+/// settings.json 中由本应用维护的键；其余键（permissions、statusLine 等）由 CLI 或用户改写，不参与比较
+const APP_MANAGED_KEY: &str = "env";
+
+/// settings.json 最近一次变化的来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsEditOrigin {
+    /// `env` 与本应用最近一次写入一致，只有 CLI 或用户维护的其他键可能不同
+    App,
+    /// `env` 被修改（用户手动编辑或其他工具）
+    External,
+    /// 没有写入记录，或文件不存在/无法解析
+    Unknown,
+}
+
+/// 只对本应用维护的 `env` 部分计算哈希，键顺序和格式不影响结果
+fn settings_content_hash(settings: &serde_json::Value) -> String {
+    use sha2::{Digest, Sha256};
+
+    let canonical = match settings.get(APP_MANAGED_KEY).and_then(|env| env.as_object()) {
+        Some(env) => {
+            let sorted: std::collections::BTreeMap<&String, &serde_json::Value> = env.iter().collect();
+            serde_json::to_string(&sorted).unwrap_or_default()
+        }
+        None => String::new(),
+    };
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+/// 读取并解析 settings.json，文件不存在或无法解析时返回 None
+fn read_settings_json(settings_path: &std::path::Path) -> Option<serde_json::Value> {
+    fs::read_to_string(settings_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// 记录本应用刚写入的 settings.json 内容哈希，之后用来区分 CLI 的改写
+pub(crate) async fn record_app_settings_write(app: &AppHandle, settings_path: &std::path::Path) {
+    let settings = match read_settings_json(settings_path) {
+        Some(settings) => settings,
+        None => return,
+    };
+    if let Err(e) = crate::commands::storage::save_app_setting(
+        app.clone(),
+        LAST_WRITE_HASH_SETTING.to_string(),
+        settings_content_hash(&settings),
+    )
+    .await
+    {
+        log::warn!("Settings monitor: Failed to record settings write hash: {}", e);
+    }
+}
+
+/// 与最近一次写入记录比较，判断当前内容的来源
+async fn settings_edit_origin(app: &AppHandle, settings: &serde_json::Value) -> SettingsEditOrigin {
+    match crate::commands::storage::get_app_setting(app.clone(), LAST_WRITE_HASH_SETTING.to_string()).await {
+        Ok(Some(hash)) if hash == settings_content_hash(settings) => SettingsEditOrigin::App,
+        Ok(Some(_)) => SettingsEditOrigin::External,
+        _ => SettingsEditOrigin::Unknown,
+    }
+}
+
+/// 监听器的检测间隔与启动延迟
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    pub check_interval_ms: u64,
+    pub startup_delay_ms: u64,
+    pub running: bool,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_ms: 1500,
+            startup_delay_ms: 5000,
+            running: false,
+        }
+    }
+}
+
 pub struct SettingsMonitor {
     last_check_time: Arc<Mutex<Option<SystemTime>>>,
     is_internal_update: Arc<Mutex<bool>>,
+    config: Mutex<MonitorConfig>,
+    task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
 impl SettingsMonitor {
@@ -39,6 +130,33 @@ impl SettingsMonitor {
         Self {
             last_check_time: Arc::new(Mutex::new(None)),
             is_internal_update: Arc::new(Mutex::new(false)),
+            config: Mutex::new(MonitorConfig::default()),
+            task: Mutex::new(None),
+        }
+    }
+
+    /// 当前生效的配置
+    pub fn config(&self) -> MonitorConfig {
+        *self.config.lock().unwrap()
+    }
+
+    /// 更新检测间隔与启动延迟，下次启动时生效
+    pub fn set_intervals(&self, check_interval_ms: u64, startup_delay_ms: u64) {
+        let mut config = self.config.lock().unwrap();
+        config.check_interval_ms = check_interval_ms;
+        config.startup_delay_ms = startup_delay_ms;
+    }
+
+    /// 停止监听任务，返回之前是否在运行
+    pub fn stop_monitoring(&self) -> bool {
+        self.config.lock().unwrap().running = false;
+        match self.task.lock().unwrap().take() {
+            Some(task) => {
+                task.abort();
+                log::info!("Settings monitor stopped");
+                true
+            }
+            None => false,
         }
     }
 
@@ -54,8 +172,12 @@ impl SettingsMonitor {
 
     /// 开始监听设置文件变化
     pub fn start_monitoring(&self, app_handle: AppHandle) -> Result<(), String> {
+        // 已在运行时先停止，保证只有一个检查任务
+        self.stop_monitoring();
+
         let last_check = self.last_check_time.clone();
         let is_internal = self.is_internal_update.clone();
+        let config = self.config();
 
         // 更新初始检查时间
         if let Ok(claude_dir) = get_claude_dir() {
@@ -70,13 +192,12 @@ impl SettingsMonitor {
 
         // 启动定期检查任务
         let app_clone = app_handle.clone();
-        tauri::async_runtime::spawn(async move {
-            // 等待5秒后开始监听，避免启动时的配置更新被误判
-            tokio::time::sleep(Duration::from_secs(5)).await;
+        let task = tauri::async_runtime::spawn(async move {
+            // 延迟一段时间后开始监听，避免启动时的配置更新被误判
+            tokio::time::sleep(Duration::from_millis(config.startup_delay_ms)).await;
             log::info!("Settings monitor: Starting file change detection after startup delay");
             
-            // 更频繁的检测间隔以提高响应性
-            let mut interval = tokio::time::interval(Duration::from_millis(1500));
+            let mut interval = tokio::time::interval(Duration::from_millis(config.check_interval_ms));
             
             loop {
                 interval.tick().await;
@@ -98,7 +219,13 @@ impl SettingsMonitor {
             }
         });
 
-        log::info!("Settings monitor started with 5s startup delay, 1.5s check interval");
+        *self.task.lock().unwrap() = Some(task);
+        self.config.lock().unwrap().running = true;
+        log::info!(
+            "Settings monitor started with {}ms startup delay, {}ms check interval",
+            config.startup_delay_ms,
+            config.check_interval_ms
+        );
         Ok(())
     }
 
@@ -143,6 +270,14 @@ impl SettingsMonitor {
         }; // MutexGuard is dropped here
 
         if needs_check {
+            // env 与上次写入一致，只有 CLI 或用户维护的其他键变化时，不提示刷新
+            if let Some(settings) = read_settings_json(&settings_path) {
+                if settings_edit_origin(app, &settings).await == SettingsEditOrigin::App {
+                    log::info!("Settings monitor: env unchanged since our last write, ignoring");
+                    return Ok(());
+                }
+            }
+
             // 文件被修改了，通知前端进行检测
             log::info!("Settings monitor: External file modification detected, notifying frontend");
             
@@ -204,7 +339,7 @@ impl SettingsMonitor {
 
     fn has_inconsistency(
         external_config: &serde_json::Value,
-        internal_vars: &std::collections::HashMap<String, String>
+        internal_vars: &std::collections::HashMap<String, String>,
     ) -> bool {
         let external_env = external_config
             .get("env")
@@ -285,16 +420,70 @@ pub async fn check_configuration_consistency(app: AppHandle) -> Result<ConfigSta
     SettingsMonitor::check_configuration_consistency(&app).await
 }
 
+/// 从 app_settings 读取已保存的毫秒数
+async fn read_ms_setting(app: &AppHandle, key: &str) -> Option<u64> {
+    crate::commands::storage::get_app_setting(app.clone(), key.to_string())
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+}
+
+/// 读取已保存的间隔配置，缺失时使用默认值
+async fn load_monitor_intervals(app: &AppHandle) -> (u64, u64) {
+    let defaults = MonitorConfig::default();
+    let check_interval_ms = read_ms_setting(app, CHECK_INTERVAL_SETTING)
+        .await
+        .unwrap_or(defaults.check_interval_ms)
+        .max(MIN_CHECK_INTERVAL_MS);
+    let startup_delay_ms = read_ms_setting(app, STARTUP_DELAY_SETTING)
+        .await
+        .unwrap_or(defaults.startup_delay_ms);
+    (check_interval_ms, startup_delay_ms)
+}
+
 /// 启动设置监听器
 #[tauri::command]
 pub async fn start_settings_monitor(app: AppHandle) -> Result<(), String> {
-    let monitor = SettingsMonitor::new();
+    let (check_interval_ms, startup_delay_ms) = load_monitor_intervals(&app).await;
+
+    // 监听器实例在启动时已注册到应用状态
+    let monitor = app.state::<SettingsMonitor>();
+    monitor.set_intervals(check_interval_ms, startup_delay_ms);
+    monitor.start_monitoring(app.clone())
+}
+
+/// 修改检测间隔与启动延迟，保存后用新配置重启监听器
+#[tauri::command]
+pub async fn configure_settings_monitor(
+    app: AppHandle,
+    check_interval_ms: u64,
+    startup_delay_ms: u64,
+) -> Result<MonitorConfig, String> {
+    if check_interval_ms < MIN_CHECK_INTERVAL_MS {
+        return Err(format!(
+            "Check interval must be at least {}ms, got {}ms",
+            MIN_CHECK_INTERVAL_MS, check_interval_ms
+        ));
+    }
+
+    crate::commands::storage::save_app_setting(
+        app.clone(),
+        CHECK_INTERVAL_SETTING.to_string(),
+        check_interval_ms.to_string(),
+    )
+    .await?;
+    crate::commands::storage::save_app_setting(
+        app.clone(),
+        STARTUP_DELAY_SETTING.to_string(),
+        startup_delay_ms.to_string(),
+    )
+    .await?;
+
+    let monitor = app.state::<SettingsMonitor>();
+    monitor.set_intervals(check_interval_ms, startup_delay_ms);
     monitor.start_monitoring(app.clone())?;
-    
-    // 存储监听器实例到应用状态
-    app.manage(monitor);
-    
-    Ok(())
+    Ok(monitor.config())
 }
 
 /// 标记即将进行内部更新
@@ -316,9 +505,12 @@ pub async fn mark_internal_settings_update(app: AppHandle) -> Result<(), String>
     Ok(())
 }
 
-/// 刷新配置（重新应用工具内配置到外部文件）
+/// 刷新配置（重新应用工具内配置到外部文件），返回实际写入的变量及写入后的校验结果
 #[tauri::command]
-pub async fn refresh_configuration(app: AppHandle, group_id: Option<i64>) -> Result<String, String> {
+pub async fn refresh_configuration(
+    app: AppHandle,
+    group_id: Option<i64>,
+) -> Result<crate::commands::claude::RefreshResult, String> {
     // 标记为内部更新
     mark_internal_settings_update(app.clone()).await?;
     
@@ -332,20 +524,21 @@ pub async fn refresh_configuration_keep_model(
     app: AppHandle, 
     group_id: Option<i64>,
     current_selected_model: String
-) -> Result<String, String> {
+) -> Result<crate::commands::claude::RefreshResult, String> {
     log::info!("Refreshing configuration while keeping current model: {}", current_selected_model);
     
     // 标记为内部更新
     mark_internal_settings_update(app.clone()).await?;
     
     // 1. 首先更新环境变量组配置
-    crate::commands::claude::update_claude_settings_with_env_group(app.clone(), group_id).await?;
+    let mut result = crate::commands::claude::update_claude_settings_with_env_group(app.clone(), group_id).await?;
     
     // 2. 然后单独设置用户当前选择的模型
     crate::commands::claude::update_claude_settings_with_model(app.clone(), current_selected_model.clone()).await?;
     
     log::info!("Configuration refreshed successfully with model: {}", current_selected_model);
-    Ok("Configuration refreshed while keeping selected model".to_string())
+    result.message = format!("{} (kept selected model {})", result.message, current_selected_model);
+    Ok(result)
 }
 
 /// 手动触发一次配置检测（用于测试）
@@ -516,9 +709,177 @@ pub async fn get_detailed_configuration_status(
         external_env,
         internal_vars: internal_env_vars,
         comparison_details,
+        monitor_config: app
+            .try_state::<SettingsMonitor>()
+            .map(|monitor| monitor.config())
+            .unwrap_or_default(),
     })
 }
 
+/// 判断 settings.json 的 `env` 是本应用写入的还是被外部修改
+#[tauri::command]
+pub async fn detect_settings_edit_origin(app: AppHandle) -> Result<SettingsEditOrigin, String> {
+    let settings_path = get_claude_dir().map_err(|e| e.to_string())?.join("settings.json");
+    match read_settings_json(&settings_path) {
+        Some(settings) => Ok(settings_edit_origin(&app, &settings).await),
+        None => Ok(SettingsEditOrigin::Unknown),
+    }
+}
+
+/// 判断环境变量是否为敏感信息（令牌、密钥等），返回前需要掩码
+pub(crate) fn is_secret_key(key: &str) -> bool {
+    let upper = key.to_uppercase();
+    ["TOKEN", "KEY", "SECRET", "PASSWORD"]
+        .iter()
+        .any(|marker| upper.contains(marker))
+}
+
+/// 逐项对比 settings.json 的 env 与数据库中已启用的环境变量
+/// 不依赖当前选择的模型，敏感值会被掩码
+#[tauri::command]
+pub async fn diff_settings_vs_db(
+    db: tauri::State<'_, crate::commands::agents::AgentDb>,
+) -> Result<Vec<ComparisonDetail>, String> {
+    use crate::commands::agents::{get_enabled_environment_variables, mask_secret};
+
+    let settings_path = get_claude_dir().map_err(|e| e.to_string())?.join("settings.json");
+    let external_env: std::collections::HashMap<String, String> = if settings_path.exists() {
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        let config: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse settings: {}", e))?;
+        config
+            .get("env")
+            .and_then(|e| e.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .map(|(k, v)| {
+                        let value = v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string());
+                        (k.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        std::collections::HashMap::new()
+    };
+
+    let internal_env = get_enabled_environment_variables(db).await?;
+
+    let mut keys: Vec<&String> = external_env.keys().chain(internal_env.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let details = keys
+        .into_iter()
+        .map(|key| {
+            let external = external_env.get(key);
+            let internal = internal_env.get(key);
+            let is_consistent = values_match(external.map(|s| s.as_str()), internal.map(|s| s.as_str()));
+            let reason = match (external, internal) {
+                (Some(_), Some(_)) if is_consistent => "一致".to_string(),
+                (Some(_), Some(_)) => "值不一致".to_string(),
+                (Some(_), None) => "仅存在于配置文件".to_string(),
+                (None, Some(_)) => "仅存在于数据库".to_string(),
+                (None, None) => unreachable!(),
+            };
+            let display = |value: Option<&String>| {
+                value.map(|v| if is_secret_key(key) { mask_secret(v) } else { v.clone() })
+            };
+
+            ComparisonDetail {
+                key: key.clone(),
+                external_value: display(external),
+                internal_value: display(internal),
+                is_consistent,
+                reason,
+            }
+        })
+        .collect();
+
+    Ok(details)
+}
+
+/// settings.json 中某个 env 键的值来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveVarSource {
+    pub key: String,
+    /// 当前值，敏感值会被掩码
+    pub value: String,
+    /// "group" 表示来自已启用的分组，"external/manual" 表示没有分组提供该值
+    pub source: String,
+    pub group_id: Option<i64>,
+    pub group_name: Option<String>,
+}
+
+/// 说明 settings.json 的 env 中每个值来自哪个已启用的环境变量分组
+/// 按分组优先级（sort_order 升序）取第一个值相同的分组；ANTHROPIC_MODEL 还会匹配 MID_* 模型
+#[tauri::command]
+pub async fn get_active_settings_source(
+    db: tauri::State<'_, crate::commands::agents::AgentDb>,
+) -> Result<Vec<ActiveVarSource>, String> {
+    use crate::commands::agents::mask_secret;
+
+    let settings_path = get_claude_dir().map_err(|e| e.to_string())?.join("settings.json");
+    if !settings_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&settings_path)
+        .map_err(|e| format!("Failed to read settings: {}", e))?;
+    let config: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    let external_env = match config.get("env").and_then(|e| e.as_object()) {
+        Some(env) => env.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    // 已启用的变量，按优先级排序：(key, value, group_id, group_name)
+    let candidates: Vec<(String, String, Option<i64>, String)> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT ev.key, ev.value, ev.group_id, COALESCE(eg.name, 'Default')
+                 FROM environment_variables ev
+                 LEFT JOIN environment_variable_groups eg ON ev.group_id = eg.id
+                 WHERE ev.enabled = 1 AND (ev.group_id IS NULL OR eg.enabled = 1)
+                 ORDER BY COALESCE(eg.sort_order, 999999) ASC, ev.sort_order ASC",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        rows
+    };
+
+    let mut keys: Vec<&String> = external_env.keys().collect();
+    keys.sort();
+
+    let sources = keys
+        .into_iter()
+        .map(|key| {
+            let raw = &external_env[key];
+            let value = raw.as_str().map(|s| s.to_string()).unwrap_or_else(|| raw.to_string());
+            let matched = candidates.iter().find(|(k, v, _, _)| k == key && v == &value).or_else(|| {
+                (key == "ANTHROPIC_MODEL")
+                    .then(|| candidates.iter().find(|(k, v, _, _)| k.starts_with("MID_") && v == &value))
+                    .flatten()
+            });
+
+            ActiveVarSource {
+                key: key.clone(),
+                value: if is_secret_key(key) { mask_secret(&value) } else { value.clone() },
+                source: if matched.is_some() { "group" } else { "external/manual" }.to_string(),
+                group_id: matched.and_then(|(_, _, group_id, _)| *group_id),
+                group_name: matched.map(|(_, _, _, name)| name.clone()),
+            }
+        })
+        .collect();
+
+    Ok(sources)
+}
+
 /// 获取用户当前选择的模型（从localStorage读取）
 #[tauri::command]
 pub async fn get_current_selected_model_from_storage(_app: AppHandle) -> Result<Option<String>, String> {
@@ -449,6 +449,178 @@ pub async fn slash_command_delete(command_id: String, project_path: Option<Strin
     Ok(format!("Deleted command: {}", command.full_command))
 }
 
+/// Substitute arguments into a command template, leaving placeholders without a
+/// matching argument untouched. `$ARGUMENTS` receives all arguments joined by spaces;
+/// `$1`, `$2`, ... receive individual arguments. Returns the text and the unfilled placeholders.
+fn substitute_command_template(template: &str, args: &[String]) -> Result<(String, Vec<String>), String> {
+    let placeholder_regex = regex::Regex::new(r"\$(\d+)").map_err(|e| e.to_string())?;
+
+    let mut missing = Vec::new();
+    let expanded = placeholder_regex.replace_all(template, |caps: &regex::Captures| {
+        let index: usize = caps[1].parse().unwrap_or(0);
+        if index == 0 || index > args.len() {
+            missing.push(format!("${}", index));
+            caps[0].to_string()
+        } else {
+            args[index - 1].clone()
+        }
+    });
+    missing.sort();
+    missing.dedup();
+
+    Ok((expanded.replace("$ARGUMENTS", &args.join(" ")), missing))
+}
+
+/// Positional placeholders the prose of a template refers to. `$N` inside fenced code
+/// blocks and inline code spans (e.g. `awk '{print $1}'` or a `!` bash line) is shell
+/// text rather than an argument slot, so it is not counted.
+fn prose_placeholders(template: &str) -> Result<Vec<String>, String> {
+    let placeholder_regex = regex::Regex::new(r"\$(\d+)").map_err(|e| e.to_string())?;
+
+    let mut placeholders = Vec::new();
+    let mut in_fence = false;
+    for line in template.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        // Odd-numbered segments between backticks are inline code
+        for prose in line.split('`').step_by(2) {
+            placeholders.extend(
+                placeholder_regex
+                    .captures_iter(prose)
+                    .map(|caps| format!("${}", &caps[1])),
+            );
+        }
+    }
+    Ok(placeholders)
+}
+
+/// Expand a command template with the given arguments.
+/// Every positional placeholder used in the prose must have a matching argument.
+fn expand_command_template(template: &str, args: &[String]) -> Result<String, String> {
+    let (expanded, unfilled) = substitute_command_template(template, args)?;
+    let used = prose_placeholders(template)?;
+    let missing: Vec<String> = unfilled.into_iter().filter(|p| used.contains(p)).collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing required arguments: {} (got {} argument(s))",
+            missing.join(", "),
+            args.len()
+        ));
+    }
+    Ok(expanded)
+}
+
+/// Find a command by full command (with or without the leading slash) or by name
+async fn find_slash_command(project_path: Option<String>, command_name: &str) -> Result<SlashCommand, String> {
+    let commands = slash_commands_list(project_path).await?;
+
+    let wanted = command_name.trim_start_matches('/');
+    commands
+        .into_iter()
+        .find(|cmd| cmd.full_command.trim_start_matches('/') == wanted || cmd.name == wanted)
+        .ok_or_else(|| format!("Command not found: {}", command_name))
+}
+
+/// The command line typed into a session to invoke a command
+fn command_invocation(command: &SlashCommand, args: &[String]) -> String {
+    if args.is_empty() {
+        command.full_command.clone()
+    } else {
+        format!("{} {}", command.full_command, args.join(" "))
+    }
+}
+
+/// Prompt text a command expands to
+fn build_command_prompt(command: &SlashCommand, args: &[String]) -> Result<(String, Vec<String>), String> {
+    if command.scope == "default" {
+        Ok((command_invocation(command, args), Vec::new()))
+    } else {
+        substitute_command_template(&command.content, args)
+    }
+}
+
+/// Result of expanding a command without running it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandPreview {
+    /// Text the command expands to; `!` bash lines and `@` file references are
+    /// resolved by Claude when the command actually runs
+    pub prompt: String,
+    /// Positional placeholders with no matching argument (left as-is in `prompt`)
+    pub unfilled_placeholders: Vec<String>,
+}
+
+/// Preview what a command expands to with the given arguments, without executing anything
+#[tauri::command]
+pub async fn slash_command_preview(
+    name: String,
+    args: Vec<String>,
+    project_path: Option<String>,
+) -> Result<SlashCommandPreview, String> {
+    debug!("Previewing slash command: {}", name);
+
+    let command = find_slash_command(project_path, &name).await?;
+    let (prompt, unfilled_placeholders) = build_command_prompt(&command, &args)?;
+
+    Ok(SlashCommandPreview {
+        prompt,
+        unfilled_placeholders,
+    })
+}
+
+/// Run a slash command against an existing session.
+/// The command is sent as the next prompt via `resume_claude_code`, the same way the
+/// prompt input sends it, so Claude executes it with its frontmatter, `!` bash lines
+/// and `@` file references. Custom commands are checked for missing arguments first.
+/// Without an explicit model the configured one for the project is used.
+/// Returns the prompt that was sent.
+#[tauri::command]
+pub async fn run_slash_command(
+    app: tauri::AppHandle,
+    session_id: String,
+    command_name: String,
+    args: Vec<String>,
+    model: Option<String>,
+) -> Result<String, String> {
+    info!("Running slash command {} in session {}", command_name, session_id);
+
+    let project_path = crate::commands::claude::find_session_project_path(&session_id)?;
+    let command = find_slash_command(Some(project_path.clone()), &command_name).await?;
+
+    if command.scope != "default" {
+        expand_command_template(&command.content, &args)?;
+    }
+    let prompt = command_invocation(&command, &args);
+
+    let model = {
+        use tauri::Manager;
+        let db = app.state::<crate::commands::agents::AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::commands::agents::resolve_effective_model_internal(
+            &conn,
+            None,
+            model.as_deref(),
+            Some(&project_path),
+        )
+        .model
+    };
+    crate::commands::claude::resume_claude_code(
+        app,
+        project_path,
+        session_id,
+        prompt.clone(),
+        model,
+        None,
+    )
+    .await?;
+
+    Ok(prompt)
+}
+
 /// Remove empty directories recursively
 fn remove_empty_dirs(dir: &Path) -> Result<()> {
     if !dir.exists() {
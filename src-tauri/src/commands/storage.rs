@@ -3,6 +3,8 @@ use rusqlite::{params, Connection, Result as SqliteResult, types::ValueRef};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value as JsonValue};
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, State};
 use super::agents::AgentDb;
 
@@ -46,6 +48,20 @@ pub struct QueryResult {
     pub last_insert_rowid: Option<i64>,
 }
 
+/// How long a dangerous-SQL confirmation token stays valid
+const DANGEROUS_SQL_TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// One-time confirmation tokens issued for high-risk SQL statements
+#[derive(Default)]
+pub struct DangerousSqlState(pub Mutex<HashMap<String, (String, Instant)>>);
+
+/// Confirmation token and impact description for a high-risk statement
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DangerousSqlConfirmation {
+    pub token: String,
+    pub description: String,
+}
+
 /// List all tables in the database
 #[tauri::command]
 pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo>, String> {
@@ -235,6 +251,7 @@ pub async fn storage_update_row(
     updates: HashMap<String, JsonValue>,
 ) -> Result<(), String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
+    ensure_writable(&conn)?;
     
     // Validate table name
     if !is_valid_table_name(&conn, &tableName)? {
@@ -290,6 +307,7 @@ pub async fn storage_delete_row(
     primaryKeyValues: HashMap<String, JsonValue>,
 ) -> Result<(), String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
+    ensure_writable(&conn)?;
     
     // Validate table name
     if !is_valid_table_name(&conn, &tableName)? {
@@ -331,6 +349,7 @@ pub async fn storage_insert_row(
     values: HashMap<String, JsonValue>,
 ) -> Result<i64, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
+    ensure_writable(&conn)?;
     
     // Validate table name
     if !is_valid_table_name(&conn, &tableName)? {
@@ -367,14 +386,37 @@ pub async fn storage_insert_row(
 #[tauri::command]
 pub async fn storage_execute_sql(
     db: State<'_, AgentDb>,
+    sql_state: State<'_, DangerousSqlState>,
     query: String,
+    confirm_token: Option<String>,
 ) -> Result<QueryResult, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     
-    // Check if it's a SELECT query
-    let is_select = query.trim().to_uppercase().starts_with("SELECT");
+    // Classify the statement so read-only mode can be enforced
+    let is_mutating = is_mutating_statement(&conn, &query)?;
+    if is_mutating {
+        ensure_writable(&conn)?;
+    }
+    
+    // High-risk statements must carry a token from storage_prepare_dangerous_sql
+    if detect_dangerous_sql(&query).is_some() {
+        let token = confirm_token
+            .ok_or("This statement is destructive and requires confirmation")?;
+        let (confirmed_sql, issued_at) = sql_state
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(&token)
+            .ok_or("Invalid or already used confirmation token")?;
+        if issued_at.elapsed() > DANGEROUS_SQL_TOKEN_TTL {
+            return Err("Confirmation token has expired".to_string());
+        }
+        if normalize_sql(&confirmed_sql) != normalize_sql(&query) {
+            return Err("Confirmation token does not match this statement".to_string());
+        }
+    }
     
-    if is_select {
+    if !is_mutating {
         // Handle SELECT queries
         let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
         let column_count = stmt.column_count();
@@ -429,6 +471,48 @@ pub async fn storage_execute_sql(
     }
 }
 
+/// Issue a one-time confirmation token for a high-risk SQL statement
+#[tauri::command]
+pub async fn storage_prepare_dangerous_sql(
+    db: State<'_, AgentDb>,
+    sql_state: State<'_, DangerousSqlState>,
+    sql: String,
+) -> Result<DangerousSqlConfirmation, String> {
+    let (kind, table) = detect_dangerous_sql(&sql)
+        .ok_or("Statement is not considered dangerous; no confirmation needed")?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let row_count = if is_valid_table_name(&conn, &table)? {
+        conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .ok()
+    } else {
+        None
+    };
+    drop(conn);
+
+    let rows = row_count
+        .map(|n| format!("{} row(s)", n))
+        .unwrap_or_else(|| "all rows".to_string());
+    let description = match kind {
+        "DROP TABLE" => format!("Drops table {} and permanently deletes its {}", table, rows),
+        "DROP VIEW" => format!("Drops view {}; the underlying tables are not changed", table),
+        "DROP INDEX" => format!("Drops index {}; no rows are deleted, but queries using it may slow down", table),
+        "DROP TRIGGER" => format!("Drops trigger {}; its actions will no longer run", table),
+        "TRUNCATE" => format!("Removes {} from {}", rows, table),
+        "DELETE" => format!("Deletes {} from {} (no WHERE clause)", rows, table),
+        _ => format!("Updates {} in {} (no WHERE clause)", rows, table),
+    };
+
+    let token = uuid::Uuid::new_v4().to_string();
+    let mut pending = sql_state.0.lock().map_err(|e| e.to_string())?;
+    pending.retain(|_, (_, issued_at)| issued_at.elapsed() <= DANGEROUS_SQL_TOKEN_TTL);
+    pending.insert(token.clone(), (sql, Instant::now()));
+
+    Ok(DangerousSqlConfirmation { token, description })
+}
+
 /// Reset the entire database (with confirmation)
 #[tauri::command]
 pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
@@ -487,6 +571,821 @@ pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Tables included in an app configuration snapshot (in insert order)
+const CONFIG_TABLES: &[&str] = &[
+    "app_settings",
+    "environment_variable_groups",
+    "environment_variables",
+    "agents",
+];
+
+/// app_settings keys that are machine-local or internal bookkeeping. They are never
+/// exported or snapshotted, and an import or restore neither clears nor overwrites them.
+const LOCAL_APP_SETTING_KEYS: &[&str] = &[
+    "claude_binary_path",
+    "claude_installation_preference",
+    "mcp_parked_servers",
+    "agent_execution_paused",
+    "settings_last_write_hash",
+    crate::commands::claude::MANAGED_ENV_KEYS_SETTING,
+    "log_level",
+    STORAGE_READ_ONLY_KEY,
+];
+
+/// Prefixes of per-project app_settings keys that are machine-local
+const LOCAL_APP_SETTING_PREFIXES: &[&str] = &["additional_dirs:"];
+
+fn is_local_app_setting(key: &str) -> bool {
+    LOCAL_APP_SETTING_KEYS.contains(&key)
+        || LOCAL_APP_SETTING_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
+
+/// Whether a config row is a machine-local app setting
+fn is_local_config_row(table: &str, row: &Map<String, JsonValue>) -> bool {
+    table == "app_settings"
+        && row
+            .get("key")
+            .and_then(|k| k.as_str())
+            .is_some_and(is_local_app_setting)
+}
+
+/// Read a config table's rows, leaving out machine-local app settings
+fn read_config_rows(conn: &Connection, table: &str) -> Result<Vec<Map<String, JsonValue>>, String> {
+    let mut rows = read_table_rows(conn, table)?;
+    rows.retain(|row| !is_local_config_row(table, row));
+    Ok(rows)
+}
+
+/// Full app configuration snapshot
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppConfigExport {
+    pub version: u32,
+    pub exported_at: String,
+    pub tables: HashMap<String, Vec<Map<String, JsonValue>>>,
+    /// Per-project MCP server approvals from `~/.claude.json` (project path -> choice keys)
+    #[serde(default)]
+    pub mcp_project_choices: Map<String, JsonValue>,
+}
+
+/// Keys under `projects.<path>` in `~/.claude.json` that record MCP server choices
+const MCP_PROJECT_CHOICE_KEYS: &[&str] = &[
+    "enabledMcpjsonServers",
+    "disabledMcpjsonServers",
+    "enableAllProjectMcpServers",
+    "disabledMcpServers",
+];
+
+/// Read the MCP project choices of every project in `~/.claude.json`
+async fn read_mcp_project_choices() -> Map<String, JsonValue> {
+    let config: JsonValue = match super::mcp::mcp_read_claude_global_config().await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or(JsonValue::Null),
+        Err(_) => JsonValue::Null,
+    };
+    let mut choices = Map::new();
+    if let Some(projects) = config.get("projects").and_then(|p| p.as_object()) {
+        for (path, project) in projects {
+            let selected: Map<String, JsonValue> = MCP_PROJECT_CHOICE_KEYS
+                .iter()
+                .filter_map(|key| project.get(*key).map(|v| (key.to_string(), v.clone())))
+                .collect();
+            if !selected.is_empty() {
+                choices.insert(path.clone(), JsonValue::Object(selected));
+            }
+        }
+    }
+    choices
+}
+
+/// Apply MCP project choices to `~/.claude.json`. Replace overwrites the choice keys of
+/// each listed project; Merge only fills keys the project doesn't have yet.
+/// Returns (applied, skipped) key counts.
+async fn apply_mcp_project_choices(
+    choices: &Map<String, JsonValue>,
+    mode: ConfigImportMode,
+) -> Result<(usize, usize), String> {
+    if choices.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let content = super::mcp::mcp_read_claude_global_config().await?;
+    let mut config: JsonValue = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse Claude config: {}", e))?;
+    let projects = config
+        .as_object_mut()
+        .ok_or("Claude config is not a JSON object")?
+        .entry("projects")
+        .or_insert_with(|| JsonValue::Object(Map::new()))
+        .as_object_mut()
+        .ok_or("Claude config 'projects' is not an object")?;
+
+    let (mut applied, mut skipped) = (0, 0);
+    for (path, project_choices) in choices {
+        let project_choices = match project_choices.as_object() {
+            Some(project_choices) => project_choices,
+            None => continue,
+        };
+        let project = projects
+            .entry(path.clone())
+            .or_insert_with(|| JsonValue::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| format!("Claude project config for {} is not an object", path))?;
+        if mode == ConfigImportMode::Replace {
+            for key in MCP_PROJECT_CHOICE_KEYS {
+                project.remove(*key);
+            }
+        }
+        for (key, value) in project_choices {
+            if !MCP_PROJECT_CHOICE_KEYS.contains(&key.as_str()) || project.contains_key(key) {
+                skipped += 1;
+                continue;
+            }
+            project.insert(key.clone(), value.clone());
+            applied += 1;
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize Claude config: {}", e))?;
+    super::mcp::mcp_write_claude_global_config(content).await?;
+    Ok((applied, skipped))
+}
+
+/// How an app configuration snapshot should be applied
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ConfigImportMode {
+    /// Keep existing rows and skip incoming rows that collide by unique key
+    Merge,
+    /// Clear each table and reload it from the snapshot
+    Replace,
+}
+
+/// Per-table counts from an app configuration import
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConfigImportResult {
+    pub imported: HashMap<String, usize>,
+    pub skipped: HashMap<String, usize>,
+}
+
+/// Export agents, environment variable groups/variables, app settings
+/// (including proxy settings) and MCP project choices as a single versioned JSON document.
+/// Agent run history is only included when `include_runs` is true.
+#[tauri::command]
+pub async fn export_app_config(
+    db: State<'_, AgentDb>,
+    include_runs: Option<bool>,
+) -> Result<String, String> {
+    let mcp_project_choices = read_mcp_project_choices().await;
+
+    let mut tables = HashMap::new();
+    let mut table_names: Vec<&str> = CONFIG_TABLES.to_vec();
+    if include_runs.unwrap_or(false) {
+        table_names.push("agent_runs");
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        for table in table_names {
+            tables.insert(table.to_string(), read_config_rows(&conn, table)?);
+        }
+    }
+
+    let export = AppConfigExport {
+        version: 1,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tables,
+        mcp_project_choices,
+    };
+
+    serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize app config: {}", e))
+}
+
+/// Import an app configuration snapshot produced by `export_app_config`
+#[tauri::command]
+pub async fn import_app_config(
+    db: State<'_, AgentDb>,
+    json_data: String,
+    mode: ConfigImportMode,
+) -> Result<ConfigImportResult, String> {
+    let export: AppConfigExport =
+        serde_json::from_str(&json_data).map_err(|e| format!("Invalid JSON format: {}", e))?;
+
+    if export.version != 1 {
+        return Err(format!(
+            "Unsupported config version: {}. This version of the app only supports version 1.",
+            export.version
+        ));
+    }
+
+    let mut result = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        import_config_tables(&conn, &export, mode)?
+    };
+
+    let (applied, skipped) = apply_mcp_project_choices(&export.mcp_project_choices, mode).await?;
+    if applied > 0 {
+        result.imported.insert("mcp_project_choices".to_string(), applied);
+    }
+    if skipped > 0 {
+        result.skipped.insert("mcp_project_choices".to_string(), skipped);
+    }
+
+    log::info!("Imported app config ({:?}): {:?}", mode, result.imported);
+    Ok(result)
+}
+
+/// Apply the database tables of a snapshot in one transaction
+fn import_config_tables(
+    conn: &Connection,
+    export: &AppConfigExport,
+    mode: ConfigImportMode,
+) -> Result<ConfigImportResult, String> {
+    // Clearing agents must not cascade to run history that isn't part of the snapshot;
+    // replace_config_tables reattaches or drops those runs itself.
+    // The pragma can't change inside a transaction, so toggle it around it.
+    if mode == ConfigImportMode::Replace {
+        conn.execute("PRAGMA foreign_keys = OFF", [])
+            .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+    }
+
+    conn.execute("BEGIN TRANSACTION", []).map_err(|e| e.to_string())?;
+
+    let result = match mode {
+        ConfigImportMode::Replace => replace_config_tables(conn, export),
+        ConfigImportMode::Merge => merge_config_tables(conn, export),
+    };
+
+    let result = match result {
+        Ok(result) => conn.execute("COMMIT", []).map(|_| result).map_err(|e| e.to_string()),
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    };
+    if mode == ConfigImportMode::Replace {
+        conn.execute("PRAGMA foreign_keys = ON", [])
+            .map_err(|e| format!("Failed to re-enable foreign keys: {}", e))?;
+    }
+    result
+}
+
+/// Clear each table present in the snapshot and reload it verbatim (ids preserved)
+fn replace_config_tables(
+    conn: &Connection,
+    export: &AppConfigExport,
+) -> Result<ConfigImportResult, String> {
+    let mut result = ConfigImportResult::default();
+    let mut table_names: Vec<&str> = CONFIG_TABLES.to_vec();
+    table_names.push("agent_runs");
+
+    // Runs and schedules kept across a replace of agents follow their agent by name,
+    // since the snapshot's agent ids may belong to different agents
+    let reattach_runs = export.tables.contains_key("agents") && !export.tables.contains_key("agent_runs");
+    if reattach_runs {
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS temp.replaced_agent_names;
+             CREATE TEMP TABLE replaced_agent_names AS SELECT id, name FROM agents;",
+        )
+        .map_err(|e| format!("Failed to remember agent names: {}", e))?;
+    }
+
+    // Delete children before parents; machine-local app settings stay
+    for table in table_names.iter().rev() {
+        if !export.tables.contains_key(*table) {
+            continue;
+        }
+        if *table == "app_settings" {
+            let keys: Vec<String> = conn
+                .prepare("SELECT key FROM app_settings")
+                .and_then(|mut stmt| {
+                    let keys = stmt.query_map([], |row| row.get(0))?.collect();
+                    keys
+                })
+                .map_err(|e| format!("Failed to read app_settings: {}", e))?;
+            for key in keys.iter().filter(|key| !is_local_app_setting(key)) {
+                conn.execute("DELETE FROM app_settings WHERE key = ?1", params![key])
+                    .map_err(|e| format!("Failed to clear app_settings: {}", e))?;
+            }
+        } else {
+            conn.execute(&format!("DELETE FROM {}", table), [])
+                .map_err(|e| format!("Failed to clear {}: {}", table, e))?;
+        }
+    }
+
+    for table in table_names {
+        if let Some(rows) = export.tables.get(table) {
+            let columns = table_columns(conn, table)?;
+            let mut imported = 0;
+            for row in rows {
+                if is_local_config_row(table, row) {
+                    *result.skipped.entry(table.to_string()).or_insert(0) += 1;
+                    continue;
+                }
+                insert_config_row(conn, table, row, &columns, false)?;
+                imported += 1;
+            }
+            result.imported.insert(table.to_string(), imported);
+        }
+    }
+
+    if reattach_runs {
+        for table in ["agent_runs", "scheduled_agent_runs"] {
+            conn.execute_batch(&format!(
+                "DELETE FROM {table} WHERE agent_id NOT IN (
+                     SELECT o.id FROM temp.replaced_agent_names o JOIN agents a ON a.name = o.name
+                 );
+                 UPDATE {table} SET agent_id = (
+                     SELECT MIN(a.id) FROM temp.replaced_agent_names o JOIN agents a ON a.name = o.name
+                     WHERE o.id = {table}.agent_id
+                 );",
+                table = table
+            ))
+            .map_err(|e| format!("Failed to reattach {}: {}", table, e))?;
+        }
+        conn.execute("DROP TABLE temp.replaced_agent_names", [])
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(result)
+}
+
+/// Merge the snapshot into existing tables, remapping ids and skipping collisions
+fn merge_config_tables(
+    conn: &Connection,
+    export: &AppConfigExport,
+) -> Result<ConfigImportResult, String> {
+    let mut result = ConfigImportResult::default();
+    let mut record = |table: &str, inserted: bool| {
+        let counts = if inserted {
+            &mut result.imported
+        } else {
+            &mut result.skipped
+        };
+        *counts.entry(table.to_string()).or_insert(0) += 1;
+    };
+    let empty = Vec::new();
+    let rows_for = |table: &str| export.tables.get(table).unwrap_or(&empty);
+
+    // App settings collide by key; machine-local keys from another machine are skipped
+    let columns = table_columns(conn, "app_settings")?;
+    for row in rows_for("app_settings") {
+        let inserted = !is_local_config_row("app_settings", row)
+            && insert_config_row(conn, "app_settings", row, &columns, true)? > 0;
+        record("app_settings", inserted);
+    }
+
+    // Groups collide by name; remember old id -> local id for variables
+    let mut group_ids: HashMap<i64, i64> = HashMap::new();
+    let columns = table_columns(conn, "environment_variable_groups")?;
+    for row in rows_for("environment_variable_groups") {
+        let mut row = row.clone();
+        let old_id = row.remove("id").and_then(|v| v.as_i64());
+        let inserted =
+            insert_config_row(conn, "environment_variable_groups", &row, &columns, true)? > 0;
+        record("environment_variable_groups", inserted);
+
+        if let (Some(old_id), Some(name)) = (old_id, row.get("name").and_then(|v| v.as_str())) {
+            let local_id: i64 = conn
+                .query_row(
+                    "SELECT id FROM environment_variable_groups WHERE name = ?1",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            group_ids.insert(old_id, local_id);
+        }
+    }
+
+    // Variables collide by (group_id, key). Checked explicitly since the unique index
+    // isn't guaranteed to exist.
+    let columns = table_columns(conn, "environment_variables")?;
+    for row in rows_for("environment_variables") {
+        let mut row = row.clone();
+        row.remove("id");
+        if let Some(old_group) = row.get("group_id").and_then(|v| v.as_i64()) {
+            match group_ids.get(&old_group) {
+                Some(local_id) => {
+                    row.insert("group_id".to_string(), JsonValue::from(*local_id));
+                }
+                None => {
+                    record("environment_variables", false);
+                    continue;
+                }
+            }
+        }
+        let key = row.get("key").and_then(|v| v.as_str()).unwrap_or_default();
+        let group_id = row.get("group_id").and_then(|v| v.as_i64());
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM environment_variables WHERE key = ?1 AND group_id IS ?2)",
+                params![key, group_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        let inserted = !exists && insert_config_row(conn, "environment_variables", &row, &columns, true)? > 0;
+        record("environment_variables", inserted);
+    }
+
+    // Agents have no unique constraint; treat the name as the key
+    let mut agent_ids: HashMap<i64, i64> = HashMap::new();
+    let columns = table_columns(conn, "agents")?;
+    for row in rows_for("agents") {
+        let mut row = row.clone();
+        let old_id = row.remove("id").and_then(|v| v.as_i64());
+        let name = row.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM agents WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .ok();
+
+        let local_id = match existing {
+            Some(id) => {
+                record("agents", false);
+                id
+            }
+            None => {
+                insert_config_row(conn, "agents", &row, &columns, false)?;
+                record("agents", true);
+                conn.last_insert_rowid()
+            }
+        };
+
+        if let Some(old_id) = old_id {
+            agent_ids.insert(old_id, local_id);
+        }
+    }
+
+    // Runs are appended for agents that were mapped above
+    let columns = table_columns(conn, "agent_runs")?;
+    for row in rows_for("agent_runs") {
+        let mut row = row.clone();
+        row.remove("id");
+        let local_agent = row
+            .get("agent_id")
+            .and_then(|v| v.as_i64())
+            .and_then(|id| agent_ids.get(&id).copied());
+
+        match local_agent {
+            Some(agent_id) => {
+                row.insert("agent_id".to_string(), JsonValue::from(agent_id));
+                insert_config_row(conn, "agent_runs", &row, &columns, false)?;
+                record("agent_runs", true);
+            }
+            None => record("agent_runs", false),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Read every row of a table as column -> value maps
+fn read_table_rows(conn: &Connection, table: &str) -> Result<Vec<Map<String, JsonValue>>, String> {
+    if !is_valid_table_name(conn, table)? {
+        return Ok(Vec::new());
+    }
+
+    let columns = table_columns(conn, table)?;
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {}", table))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            let mut row_map = Map::new();
+            for (idx, col) in columns.iter().enumerate() {
+                let value = match row.get_ref(idx)? {
+                    ValueRef::Null => JsonValue::Null,
+                    ValueRef::Integer(i) => JsonValue::Number(serde_json::Number::from(i)),
+                    ValueRef::Real(f) => serde_json::Number::from_f64(f)
+                        .map(JsonValue::Number)
+                        .unwrap_or_else(|| JsonValue::String(f.to_string())),
+                    ValueRef::Text(s) => JsonValue::String(String::from_utf8_lossy(s).to_string()),
+                    ValueRef::Blob(b) => JsonValue::String(base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        b,
+                    )),
+                };
+                row_map.insert(col.clone(), value);
+            }
+            Ok(row_map)
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+/// Get the column names of a table
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| e.to_string())?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(columns)
+}
+
+/// Insert a row, ignoring keys that aren't columns of the table.
+/// Returns the number of rows inserted (0 when ignored on conflict).
+fn insert_config_row(
+    conn: &Connection,
+    table: &str,
+    row: &Map<String, JsonValue>,
+    columns: &[String],
+    or_ignore: bool,
+) -> Result<usize, String> {
+    let mut names = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    for (name, value) in row {
+        if columns.contains(name) {
+            names.push(name.as_str());
+            values.push(json_to_sql_value(value)?);
+        }
+    }
+
+    if names.is_empty() {
+        return Ok(0);
+    }
+
+    let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+    let query = format!(
+        "INSERT {}INTO {} ({}) VALUES ({})",
+        if or_ignore { "OR IGNORE " } else { "" },
+        table,
+        names.join(", "),
+        placeholders.join(", ")
+    );
+
+    conn.execute(&query, rusqlite::params_from_iter(values.iter().map(|p| p.as_ref())))
+        .map_err(|e| format!("Failed to import row into {}: {}", table, e))
+}
+
+/// Tables captured by a settings snapshot; proxy settings live in app_settings.
+/// Machine-local app settings are left out and survive a restore.
+const SNAPSHOT_TABLES: &[&str] = &[
+    "app_settings",
+    "environment_variable_groups",
+    "environment_variables",
+];
+
+/// Id of a row in settings_snapshots
+pub type SnapshotId = i64;
+
+/// A stored settings snapshot, without its payload
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingsSnapshotInfo {
+    pub id: SnapshotId,
+    pub label: String,
+    pub created_at: String,
+    /// Whether settings.json existed when the snapshot was taken
+    pub has_settings_json: bool,
+}
+
+fn claude_settings_path() -> Result<std::path::PathBuf, String> {
+    crate::commands::claude::get_claude_dir()
+        .map(|dir| dir.join("settings.json"))
+        .map_err(|e| e.to_string())
+}
+
+/// Snapshot env var groups/variables, app settings (including proxy) and
+/// `~/.claude/settings.json` so they can be restored later
+#[tauri::command]
+pub async fn create_settings_snapshot(
+    db: State<'_, AgentDb>,
+    label: String,
+) -> Result<SnapshotId, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Snapshot label cannot be empty".to_string());
+    }
+
+    let settings_json = match claude_settings_path()? {
+        path if path.exists() => Some(
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+        ),
+        _ => None,
+    };
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut tables = HashMap::new();
+    for table in SNAPSHOT_TABLES {
+        tables.insert(table.to_string(), read_config_rows(&conn, table)?);
+    }
+    let snapshot = AppConfigExport {
+        version: 1,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tables,
+        mcp_project_choices: Map::new(),
+    };
+    let data = serde_json::to_string(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO settings_snapshots (label, data, settings_json) VALUES (?1, ?2, ?3)",
+        params![label, data, settings_json],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    log::info!("Created settings snapshot {} ('{}')", id, label);
+    Ok(id)
+}
+
+/// List settings snapshots, newest first
+#[tauri::command]
+pub async fn list_settings_snapshots(
+    db: State<'_, AgentDb>,
+) -> Result<Vec<SettingsSnapshotInfo>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, label, created_at, settings_json IS NOT NULL
+             FROM settings_snapshots ORDER BY created_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let snapshots = stmt
+        .query_map([], |row| {
+            Ok(SettingsSnapshotInfo {
+                id: row.get(0)?,
+                label: row.get(1)?,
+                created_at: row.get(2)?,
+                has_settings_json: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(snapshots)
+}
+
+/// Delete a settings snapshot
+#[tauri::command]
+pub async fn delete_settings_snapshot(db: State<'_, AgentDb>, id: SnapshotId) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM settings_snapshots WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Settings snapshot not found: {}", id));
+    }
+    Ok(())
+}
+
+/// Restore a settings snapshot. The database tables are replaced in one transaction;
+/// settings.json is then rewritten as an internal update so the monitor stays quiet.
+#[tauri::command]
+pub async fn restore_settings_snapshot(app: AppHandle, id: SnapshotId) -> Result<(), String> {
+    let settings_json = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let (data, settings_json): (String, Option<String>) = conn
+            .query_row(
+                "SELECT data, settings_json FROM settings_snapshots WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Settings snapshot not found: {}", e))?;
+        let snapshot: AppConfigExport = serde_json::from_str(&data)
+            .map_err(|e| format!("Settings snapshot {} is corrupt: {}", id, e))?;
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        replace_config_tables(&tx, &snapshot)?;
+        tx.commit().map_err(|e| e.to_string())?;
+        settings_json
+    };
+
+    if let Some(content) = settings_json {
+        crate::commands::settings_monitor::mark_internal_settings_update(app.clone()).await?;
+        let path = claude_settings_path()?;
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        crate::commands::settings_monitor::record_app_settings_write(&app, &path).await;
+    }
+
+    log::info!("Restored settings snapshot {}", id);
+    Ok(())
+}
+
+/// app_settings key that puts the storage browser into read-only mode
+pub const STORAGE_READ_ONLY_KEY: &str = "storage_read_only";
+
+/// Whether the storage browser is in read-only mode (defaults to false)
+fn is_storage_read_only(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        params![STORAGE_READ_ONLY_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value == "true")
+    .unwrap_or(false)
+}
+
+/// Reject writes while read-only mode is enabled
+fn ensure_writable(conn: &Connection) -> Result<(), String> {
+    if is_storage_read_only(conn) {
+        return Err("Storage is in read-only mode; disable it in settings to modify data".to_string());
+    }
+    Ok(())
+}
+
+/// Classify a SQL statement as mutating or not, using SQLite's own analysis
+/// of the prepared statement rather than guessing from the leading keyword.
+fn is_mutating_statement(conn: &Connection, sql: &str) -> Result<bool, String> {
+    let stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    Ok(!stmt.readonly())
+}
+
+/// Collapse whitespace and strip comments so equivalent statements compare equal
+fn normalize_sql(sql: &str) -> String {
+    static LINE_COMMENTS: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    static BLOCK_COMMENTS: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let line_comments = LINE_COMMENTS.get_or_init(|| regex::Regex::new(r"--[^\n]*").unwrap());
+    let block_comments = BLOCK_COMMENTS.get_or_init(|| regex::Regex::new(r"(?s)/\*.*?\*/").unwrap());
+    let sql = block_comments.replace_all(sql, " ");
+    let sql = line_comments.replace_all(&sql, " ");
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(';')
+        .trim()
+        .to_string()
+}
+
+/// Whether the statement has a WHERE of its own, i.e. one outside parentheses and
+/// string literals. A WHERE inside a subquery doesn't restrict the outer statement.
+fn has_top_level_where(sql: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut word = String::new();
+    for c in sql.chars().chain(std::iter::once(' ')) {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if depth == 0 && word.eq_ignore_ascii_case("WHERE") {
+            return true;
+        }
+        word.clear();
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '[' => quote = Some(']'),
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Detect high-risk statements: DROP, TRUNCATE, and DELETE/UPDATE without WHERE.
+/// Returns the statement kind and the affected object name.
+fn detect_dangerous_sql(sql: &str) -> Option<(&'static str, String)> {
+    static PATTERNS: std::sync::OnceLock<Vec<(&'static str, regex::Regex)>> = std::sync::OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        let name = r#"["`\[]?([\w.]+)["`\]]?"#;
+        [
+            ("DROP TABLE", format!(r"(?i)^DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?{}", name)),
+            ("DROP VIEW", format!(r"(?i)^DROP\s+VIEW\s+(?:IF\s+EXISTS\s+)?{}", name)),
+            ("DROP INDEX", format!(r"(?i)^DROP\s+INDEX\s+(?:IF\s+EXISTS\s+)?{}", name)),
+            ("DROP TRIGGER", format!(r"(?i)^DROP\s+TRIGGER\s+(?:IF\s+EXISTS\s+)?{}", name)),
+            ("TRUNCATE", format!(r"(?i)^TRUNCATE\s+(?:TABLE\s+)?{}", name)),
+            ("DELETE", format!(r"(?i)^DELETE\s+FROM\s+{}", name)),
+            ("UPDATE", format!(r"(?i)^UPDATE\s+(?:OR\s+\w+\s+)?{}\s+SET\b", name)),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| (kind, regex::Regex::new(&pattern).unwrap()))
+        .collect()
+    });
+
+    let normalized = normalize_sql(sql);
+    let has_where = has_top_level_where(&normalized);
+
+    for (kind, re) in patterns.iter() {
+        if let Some(caps) = re.captures(&normalized) {
+            if (*kind == "DELETE" || *kind == "UPDATE") && has_where {
+                return None;
+            }
+            return Some((*kind, caps[1].to_string()));
+        }
+    }
+
+    None
+}
+
 /// Helper function to validate table name exists
 fn is_valid_table_name(conn: &Connection, table_name: &str) -> Result<bool, String> {
     let count: i64 = conn
@@ -548,9 +1447,157 @@ pub async fn save_app_setting(app: AppHandle, key: String, value: String) -> Res
         params![key, value],
     )
     .map_err(|e| format!("Failed to save setting: {}", e))?;
+    drop(conn);
+
+    if key == super::agents::MAX_LIVE_OUTPUT_SETTING {
+        super::agents::apply_max_live_output_setting(&app);
+    }
     
     Ok(())
 }
 
+/// Size of one part of the app's on-disk footprint
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct StorageEntry {
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+    pub file_count: u64,
+    /// Entries that could not be read (e.g. permission denied) and were skipped
+    pub unreadable_entries: u64,
+}
+
+/// Disk usage breakdown of the app's data and Claude's project history
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageReport {
+    /// agents.db plus its WAL/SHM files
+    pub agents_db: StorageEntry,
+    /// Full output logs of agent runs, including post-run hook results and retained stderr
+    pub run_logs: StorageEntry,
+    /// Checkpoint timelines under ~/.claude/projects/*/.timelines
+    pub checkpoints: StorageEntry,
+    /// Application log files
+    pub logs: StorageEntry,
+    /// Everything else in the app data and cache directories
+    pub cache: StorageEntry,
+    /// All of ~/.claude/projects, including checkpoints
+    pub claude_projects: StorageEntry,
+}
+
+impl StorageEntry {
+    fn new(name: &str, path: &std::path::Path) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add_file(&mut self, metadata: &std::fs::Metadata) {
+        self.bytes += metadata.len();
+        self.file_count += 1;
+    }
+}
+
+/// Walk a directory once, handing every readable file to `visit`.
+/// Unreadable entries are counted rather than aborting the walk.
+fn walk_storage_dir<F>(root: &std::path::Path, mut visit: F) -> u64
+where
+    F: FnMut(&walkdir::DirEntry, &std::fs::Metadata),
+{
+    let mut unreadable = 0;
+    if !root.exists() {
+        return unreadable;
+    }
+    for entry in walkdir::WalkDir::new(root) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => {
+                unreadable += 1;
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        match entry.metadata() {
+            Ok(metadata) => visit(&entry, &metadata),
+            Err(_) => unreadable += 1,
+        }
+    }
+    unreadable
+}
+
+/// Report how much disk space the app and Claude's project history use
+#[tauri::command]
+pub async fn get_app_storage_report(app: AppHandle) -> Result<StorageReport, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let app_cache_dir = app.path().app_cache_dir().ok();
+    let log_dir = crate::logger::log_file_path().and_then(|p| p.parent().map(|d| d.to_path_buf()));
+    let projects_dir = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude")
+        .join("projects");
+
+    tokio::task::spawn_blocking(move || {
+        let run_logs_dir = app_data_dir.join("run_logs");
+        let mut agents_db = StorageEntry::new("agents_db", &app_data_dir.join("agents.db"));
+        let mut run_logs = StorageEntry::new("run_logs", &run_logs_dir);
+        let mut cache = StorageEntry::new("cache", &app_data_dir);
+        let mut logs = StorageEntry::new("logs", log_dir.as_deref().unwrap_or(&app_data_dir));
+        let mut checkpoints = StorageEntry::new("checkpoints", &projects_dir);
+        let mut claude_projects = StorageEntry::new("claude_projects", &projects_dir);
+
+        let log_dir_ref = log_dir.as_deref();
+        let unreadable = walk_storage_dir(&app_data_dir, |entry, metadata| {
+            let file_name = entry.file_name().to_string_lossy();
+            if entry.depth() == 1 && file_name.starts_with("agents.db") {
+                agents_db.add_file(metadata);
+            } else if entry.path().starts_with(&run_logs_dir) {
+                run_logs.add_file(metadata);
+            } else if log_dir_ref.is_some_and(|dir| entry.path().starts_with(dir)) {
+                // Logs are walked separately below
+            } else {
+                cache.add_file(metadata);
+            }
+        });
+        cache.unreadable_entries += unreadable;
+
+        // The log directory may or may not sit inside the app data directory
+        if let Some(dir) = log_dir_ref {
+            let unreadable = walk_storage_dir(dir, |_, metadata| logs.add_file(metadata));
+            logs.unreadable_entries += unreadable;
+        }
+
+        if let Some(cache_dir) = app_cache_dir.filter(|dir| !dir.starts_with(&app_data_dir)) {
+            let unreadable = walk_storage_dir(&cache_dir, |_, metadata| cache.add_file(metadata));
+            cache.unreadable_entries += unreadable;
+        }
+
+        let unreadable = walk_storage_dir(&projects_dir, |entry, metadata| {
+            claude_projects.add_file(metadata);
+            if entry.path().components().any(|c| c.as_os_str() == ".timelines") {
+                checkpoints.add_file(metadata);
+            }
+        });
+        claude_projects.unreadable_entries = unreadable;
+        checkpoints.unreadable_entries = unreadable;
+
+        StorageReport {
+            agents_db,
+            run_logs,
+            checkpoints,
+            logs,
+            cache,
+            claude_projects,
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to compute storage report: {}", e))
+}
+
 /// Initialize the agents database (re-exported from agents module)
 use super::agents::init_database; 
\ No newline at end of file
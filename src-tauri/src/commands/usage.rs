@@ -4,7 +4,7 @@ use serde_json;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use tauri::command;
+use tauri::{command, AppHandle, Emitter};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UsageEntry {
@@ -19,7 +19,7 @@ pub struct UsageEntry {
     project_path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UsageStats {
     total_cost: f64,
     total_tokens: u64,
@@ -33,7 +33,7 @@ pub struct UsageStats {
     by_project: Vec<ProjectUsage>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelUsage {
     model: String,
     total_cost: f64,
@@ -45,7 +45,7 @@ pub struct ModelUsage {
     session_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyUsage {
     date: String,
     total_cost: f64,
@@ -53,7 +53,23 @@ pub struct DailyUsage {
     models_used: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Projected cost of a run, split by input and output
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CostEstimate {
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    /// Prices per million tokens
+    input_price: f64,
+    output_price: f64,
+    input_cost: f64,
+    output_cost: f64,
+    total_cost: f64,
+    /// True when the input token count came from scanning the project
+    input_from_project_scan: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectUsage {
     project_path: String,
     project_name: String,
@@ -129,14 +145,10 @@ struct UsageData {
     cache_read_input_tokens: Option<u64>,
 }
 
-fn calculate_cost(model: &str, usage: &UsageData) -> f64 {
-    let input_tokens = usage.input_tokens.unwrap_or(0) as f64;
-    let output_tokens = usage.output_tokens.unwrap_or(0) as f64;
-    let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0) as f64;
-    let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0) as f64;
-
-    // Calculate cost based on model
-    let (input_price, output_price, cache_write_price, cache_read_price) = match model {
+/// Per-million-token prices (input, output, cache write, cache read) for a model,
+/// or None when no pricing is known for it
+fn model_prices(model: &str) -> Option<(f64, f64, f64, f64)> {
+    let prices = match model {
         // Claude 3.5 Haiku
         m if m.contains("claude-3-5-haiku") || m == "haiku" => (
             HAIKU_3_5_INPUT_PRICE,
@@ -179,9 +191,20 @@ fn calculate_cost(model: &str, usage: &UsageData) -> f64 {
             SONNET_4_CACHE_WRITE_PRICE,
             SONNET_4_CACHE_READ_PRICE,
         ),
-        // Unknown models - return 0 to avoid incorrect cost estimations
-        _ => (0.0, 0.0, 0.0, 0.0),
+        _ => return None,
     };
+    Some(prices)
+}
+
+fn calculate_cost(model: &str, usage: &UsageData) -> f64 {
+    let input_tokens = usage.input_tokens.unwrap_or(0) as f64;
+    let output_tokens = usage.output_tokens.unwrap_or(0) as f64;
+    let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0) as f64;
+    let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0) as f64;
+
+    // Unknown models - return 0 to avoid incorrect cost estimations
+    let (input_price, output_price, cache_write_price, cache_read_price) =
+        model_prices(model).unwrap_or((0.0, 0.0, 0.0, 0.0));
 
     // Calculate cost (prices are per million tokens)
     let cost = (input_tokens * input_price / 1_000_000.0)
@@ -343,67 +366,33 @@ fn get_all_usage_entries(claude_path: &PathBuf) -> Vec<UsageEntry> {
     all_entries
 }
 
-#[command]
-pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
-
-    let all_entries = get_all_usage_entries(&claude_path);
-
-    if all_entries.is_empty() {
-        return Ok(UsageStats {
-            total_cost: 0.0,
-            total_tokens: 0,
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_cache_creation_tokens: 0,
-            total_cache_read_tokens: 0,
-            total_sessions: 0,
-            by_model: vec![],
-            by_date: vec![],
-            by_project: vec![],
-        });
-    }
-
-    // Filter by days if specified
-    let filtered_entries = if let Some(days) = days {
-        let cutoff = Local::now().naive_local().date() - chrono::Duration::days(days as i64);
-        all_entries
-            .into_iter()
-            .filter(|e| {
-                if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
-                    dt.naive_local().date() >= cutoff
-                } else {
-                    false
-                }
-            })
-            .collect()
-    } else {
-        all_entries
-    };
-
-    // Calculate aggregated stats
-    let mut total_cost = 0.0;
-    let mut total_input_tokens = 0u64;
-    let mut total_output_tokens = 0u64;
-    let mut total_cache_creation_tokens = 0u64;
-    let mut total_cache_read_tokens = 0u64;
-
-    let mut model_stats: HashMap<String, ModelUsage> = HashMap::new();
-    let mut daily_stats: HashMap<String, DailyUsage> = HashMap::new();
-    let mut project_stats: HashMap<String, ProjectUsage> = HashMap::new();
+/// Running usage totals; entries can be added one at a time and stats taken at any point
+#[derive(Default)]
+struct UsageAccumulator {
+    total_cost: f64,
+    total_input_tokens: u64,
+    total_output_tokens: u64,
+    total_cache_creation_tokens: u64,
+    total_cache_read_tokens: u64,
+    total_sessions: u64,
+    model_stats: HashMap<String, ModelUsage>,
+    daily_stats: HashMap<String, DailyUsage>,
+    project_stats: HashMap<String, ProjectUsage>,
+}
 
-    for entry in &filtered_entries {
+impl UsageAccumulator {
+    fn add(&mut self, entry: &UsageEntry) {
         // Update totals
-        total_cost += entry.cost;
-        total_input_tokens += entry.input_tokens;
-        total_output_tokens += entry.output_tokens;
-        total_cache_creation_tokens += entry.cache_creation_tokens;
-        total_cache_read_tokens += entry.cache_read_tokens;
+        self.total_cost += entry.cost;
+        self.total_input_tokens += entry.input_tokens;
+        self.total_output_tokens += entry.output_tokens;
+        self.total_cache_creation_tokens += entry.cache_creation_tokens;
+        self.total_cache_read_tokens += entry.cache_read_tokens;
+        self.total_sessions += 1;
 
         // Update model stats
-        let model_stat = model_stats
+        let model_stat = self
+            .model_stats
             .entry(entry.model.clone())
             .or_insert(ModelUsage {
                 model: entry.model.clone(),
@@ -430,7 +419,7 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
             .next()
             .unwrap_or(&entry.timestamp)
             .to_string();
-        let daily_stat = daily_stats.entry(date.clone()).or_insert(DailyUsage {
+        let daily_stat = self.daily_stats.entry(date.clone()).or_insert(DailyUsage {
             date,
             total_cost: 0.0,
             total_tokens: 0,
@@ -447,7 +436,7 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
 
         // Update project stats
         let project_stat =
-            project_stats
+            self.project_stats
                 .entry(entry.project_path.clone())
                 .or_insert(ProjectUsage {
                     project_path: entry.project_path.clone(),
@@ -473,34 +462,87 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
         }
     }
 
-    let total_tokens = total_input_tokens
-        + total_output_tokens
-        + total_cache_creation_tokens
-        + total_cache_read_tokens;
-    let total_sessions = filtered_entries.len() as u64;
-
-    // Convert hashmaps to sorted vectors
-    let mut by_model: Vec<ModelUsage> = model_stats.into_values().collect();
-    by_model.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
-
-    let mut by_date: Vec<DailyUsage> = daily_stats.into_values().collect();
-    by_date.sort_by(|a, b| b.date.cmp(&a.date));
-
-    let mut by_project: Vec<ProjectUsage> = project_stats.into_values().collect();
-    by_project.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
-
-    Ok(UsageStats {
-        total_cost,
-        total_tokens,
-        total_input_tokens,
-        total_output_tokens,
-        total_cache_creation_tokens,
-        total_cache_read_tokens,
-        total_sessions,
-        by_model,
-        by_date,
-        by_project,
-    })
+    /// Current totals as sorted stats; costs only the number of groups, not entries
+    fn stats(&self) -> UsageStats {
+        let total_tokens = self.total_input_tokens
+            + self.total_output_tokens
+            + self.total_cache_creation_tokens
+            + self.total_cache_read_tokens;
+
+        // Convert hashmaps to sorted vectors
+        let mut by_model: Vec<ModelUsage> = self.model_stats.values().cloned().collect();
+        by_model.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
+
+        let mut by_date: Vec<DailyUsage> = self.daily_stats.values().cloned().collect();
+        by_date.sort_by(|a, b| b.date.cmp(&a.date));
+
+        let mut by_project: Vec<ProjectUsage> = self.project_stats.values().cloned().collect();
+        by_project.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
+
+        UsageStats {
+            total_cost: self.total_cost,
+            total_tokens,
+            total_input_tokens: self.total_input_tokens,
+            total_output_tokens: self.total_output_tokens,
+            total_cache_creation_tokens: self.total_cache_creation_tokens,
+            total_cache_read_tokens: self.total_cache_read_tokens,
+            total_sessions: self.total_sessions,
+            by_model,
+            by_date,
+            by_project,
+        }
+    }
+}
+
+fn aggregate_usage(entries: &[UsageEntry]) -> UsageStats {
+    let mut totals = UsageAccumulator::default();
+    for entry in entries {
+        totals.add(entry);
+    }
+    totals.stats()
+}
+
+#[command]
+pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
+
+    let all_entries = get_all_usage_entries(&claude_path);
+
+    if all_entries.is_empty() {
+        return Ok(UsageStats {
+            total_cost: 0.0,
+            total_tokens: 0,
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            total_cache_creation_tokens: 0,
+            total_cache_read_tokens: 0,
+            total_sessions: 0,
+            by_model: vec![],
+            by_date: vec![],
+            by_project: vec![],
+        });
+    }
+
+    // Filter by days if specified
+    let filtered_entries = if let Some(days) = days {
+        let cutoff = Local::now().naive_local().date() - chrono::Duration::days(days as i64);
+        all_entries
+            .into_iter()
+            .filter(|e| {
+                if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
+                    dt.naive_local().date() >= cutoff
+                } else {
+                    false
+                }
+            })
+            .collect()
+    } else {
+        all_entries
+    };
+
+    Ok(aggregate_usage(&filtered_entries))
 }
 
 #[command]
@@ -538,139 +580,98 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
         })
         .collect();
 
-    if filtered_entries.is_empty() {
-        return Ok(UsageStats {
-            total_cost: 0.0,
-            total_tokens: 0,
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            total_cache_creation_tokens: 0,
-            total_cache_read_tokens: 0,
-            total_sessions: 0,
-            by_model: vec![],
-            by_date: vec![],
-            by_project: vec![],
-        });
-    }
+    Ok(aggregate_usage(&filtered_entries))
+}
 
-    // Calculate aggregated stats (same logic as get_usage_stats)
-    let mut total_cost = 0.0;
-    let mut total_input_tokens = 0u64;
-    let mut total_output_tokens = 0u64;
-    let mut total_cache_creation_tokens = 0u64;
-    let mut total_cache_read_tokens = 0u64;
+/// Progress payload emitted while streaming usage stats
+#[derive(Debug, Serialize, Clone)]
+pub struct UsageStatsProgress {
+    processed_projects: usize,
+    total_projects: usize,
+    stats: UsageStats,
+}
 
-    let mut model_stats: HashMap<String, ModelUsage> = HashMap::new();
-    let mut daily_stats: HashMap<String, DailyUsage> = HashMap::new();
-    let mut project_stats: HashMap<String, ProjectUsage> = HashMap::new();
+fn parse_date_bound(value: &str, label: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").or_else(|_| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|dt| dt.naive_local().date())
+            .map_err(|e| format!("Invalid {} date: {}", label, e))
+    })
+}
 
-    for entry in &filtered_entries {
-        // Update totals
-        total_cost += entry.cost;
-        total_input_tokens += entry.input_tokens;
-        total_output_tokens += entry.output_tokens;
-        total_cache_creation_tokens += entry.cache_creation_tokens;
-        total_cache_read_tokens += entry.cache_read_tokens;
+/// Collect usage stats project by project, emitting `usage-stats-partial` with
+/// running totals after each project and `usage-stats-complete` at the end.
+#[command]
+pub async fn stream_usage_stats(
+    app: AppHandle,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<(), String> {
+    let start = start_date
+        .as_deref()
+        .map(|s| parse_date_bound(s, "start"))
+        .transpose()?;
+    let end = end_date
+        .as_deref()
+        .map(|s| parse_date_bound(s, "end"))
+        .transpose()?;
 
-        // Update model stats
-        let model_stat = model_stats
-            .entry(entry.model.clone())
-            .or_insert(ModelUsage {
-                model: entry.model.clone(),
-                total_cost: 0.0,
-                total_tokens: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                cache_creation_tokens: 0,
-                cache_read_tokens: 0,
-                session_count: 0,
-            });
-        model_stat.total_cost += entry.cost;
-        model_stat.input_tokens += entry.input_tokens;
-        model_stat.output_tokens += entry.output_tokens;
-        model_stat.cache_creation_tokens += entry.cache_creation_tokens;
-        model_stat.cache_read_tokens += entry.cache_read_tokens;
-        model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
-        model_stat.session_count += 1;
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
 
-        // Update daily stats
-        let date = entry
-            .timestamp
-            .split('T')
-            .next()
-            .unwrap_or(&entry.timestamp)
-            .to_string();
-        let daily_stat = daily_stats.entry(date.clone()).or_insert(DailyUsage {
-            date,
-            total_cost: 0.0,
-            total_tokens: 0,
-            models_used: vec![],
-        });
-        daily_stat.total_cost += entry.cost;
-        daily_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
-        if !daily_stat.models_used.contains(&entry.model) {
-            daily_stat.models_used.push(entry.model.clone());
-        }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut project_dirs: Vec<(PathBuf, String)> = fs::read_dir(claude_path.join("projects"))
+            .map(|projects| {
+                projects
+                    .flatten()
+                    .filter(|p| p.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                    .map(|p| (p.path(), p.file_name().to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        project_dirs.sort_by_key(|(_, name)| name.clone());
+
+        let total_projects = project_dirs.len();
+        let mut processed_hashes = HashSet::new();
+        let mut totals = UsageAccumulator::default();
+
+        for (index, (project_path, project_name)) in project_dirs.into_iter().enumerate() {
+            let mut files: Vec<PathBuf> = walkdir::WalkDir::new(&project_path)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+                .map(|e| e.path().to_path_buf())
+                .collect();
+            files.sort_by_cached_key(|path| get_earliest_timestamp(path));
+
+            for path in files {
+                parse_jsonl_file(&path, &project_name, &mut processed_hashes)
+                    .iter()
+                    .filter(|e| match DateTime::parse_from_rfc3339(&e.timestamp) {
+                        Ok(dt) => {
+                            let date = dt.naive_local().date();
+                            start.is_none_or(|s| date >= s) && end.is_none_or(|u| date <= u)
+                        }
+                        Err(_) => false,
+                    })
+                    .for_each(|entry| totals.add(entry));
+            }
 
-        // Update project stats
-        let project_stat =
-            project_stats
-                .entry(entry.project_path.clone())
-                .or_insert(ProjectUsage {
-                    project_path: entry.project_path.clone(),
-                    project_name: entry
-                        .project_path
-                        .split('/')
-                        .last()
-                        .unwrap_or(&entry.project_path)
-                        .to_string(),
-                    total_cost: 0.0,
-                    total_tokens: 0,
-                    session_count: 0,
-                    last_used: entry.timestamp.clone(),
-                });
-        project_stat.total_cost += entry.cost;
-        project_stat.total_tokens += entry.input_tokens
-            + entry.output_tokens
-            + entry.cache_creation_tokens
-            + entry.cache_read_tokens;
-        project_stat.session_count += 1;
-        if entry.timestamp > project_stat.last_used {
-            project_stat.last_used = entry.timestamp.clone();
+            let _ = app.emit(
+                "usage-stats-partial",
+                UsageStatsProgress {
+                    processed_projects: index + 1,
+                    total_projects,
+                    stats: totals.stats(),
+                },
+            );
         }
-    }
 
-    let total_tokens = total_input_tokens
-        + total_output_tokens
-        + total_cache_creation_tokens
-        + total_cache_read_tokens;
-    let total_sessions = filtered_entries.len() as u64;
-
-    // Convert hashmaps to sorted vectors
-    let mut by_model: Vec<ModelUsage> = model_stats.into_values().collect();
-    by_model.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
-
-    let mut by_date: Vec<DailyUsage> = daily_stats.into_values().collect();
-    by_date.sort_by(|a, b| b.date.cmp(&a.date));
-
-    let mut by_project: Vec<ProjectUsage> = project_stats.into_values().collect();
-    by_project.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
-
-    Ok(UsageStats {
-        total_cost,
-        total_tokens,
-        total_input_tokens,
-        total_output_tokens,
-        total_cache_creation_tokens,
-        total_cache_read_tokens,
-        total_sessions,
-        by_model,
-        by_date,
-        by_project,
+        let _ = app.emit("usage-stats-complete", totals.stats());
     })
+    .await
+    .map_err(|e| format!("Usage stats task failed: {}", e))
 }
 
 #[command]
@@ -717,8 +718,8 @@ pub fn get_session_stats(
         .filter(|e| {
             if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
                 let date = dt.date_naive();
-                let is_after_since = since_date.map_or(true, |s| date >= s);
-                let is_before_until = until_date.map_or(true, |u| date <= u);
+                let is_after_since = since_date.is_none_or(|s| date >= s);
+                let is_before_until = until_date.is_none_or(|u| date <= u);
                 is_after_since && is_before_until
             } else {
                 false
@@ -767,3 +768,44 @@ pub fn get_session_stats(
 
     Ok(by_session)
 }
+
+/// Projected cost of a run before it starts. When `estimated_input_tokens` is omitted,
+/// the input size is estimated from `project_path` the same way
+/// `estimate_project_context_tokens` does. Fails for models with no known pricing.
+#[command]
+pub async fn estimate_run_cost(
+    model: String,
+    estimated_input_tokens: Option<u64>,
+    estimated_output_tokens: u64,
+    project_path: Option<String>,
+) -> Result<CostEstimate, String> {
+    let (input_price, output_price, _, _) = model_prices(&model)
+        .ok_or_else(|| format!("No pricing configured for model '{}'", model))?;
+
+    let (input_tokens, input_from_project_scan) = match (estimated_input_tokens, project_path) {
+        (Some(tokens), _) => (tokens, false),
+        (None, Some(project_path)) => {
+            let estimate =
+                crate::commands::claude::estimate_project_context_tokens(project_path, None, None).await?;
+            (estimate.total_tokens, true)
+        }
+        (None, None) => {
+            return Err("Either estimated_input_tokens or project_path is required".to_string())
+        }
+    };
+
+    let input_cost = input_tokens as f64 * input_price / 1_000_000.0;
+    let output_cost = estimated_output_tokens as f64 * output_price / 1_000_000.0;
+
+    Ok(CostEstimate {
+        model,
+        input_tokens,
+        output_tokens: estimated_output_tokens,
+        input_price,
+        output_price,
+        input_cost,
+        output_cost,
+        total_cost: input_cost + output_cost,
+        input_from_project_scan,
+    })
+}
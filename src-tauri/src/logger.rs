@@ -1,5 +1,59 @@
 use log::LevelFilter;
 use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 日志文件超过该大小时在启动时轮转
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 获取日志文件路径（与 Tauri app_data_dir 保持一致）
+pub fn log_file_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| {
+        dir.join("termiclaude.asterisk.so")
+            .join("logs")
+            .join("termiclaude.log")
+    })
+}
+
+/// 打开日志文件，必要时创建目录并轮转旧日志
+fn open_log_file() -> Option<File> {
+    let path = log_file_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok()?;
+    }
+
+    if let Ok(metadata) = std::fs::metadata(&path) {
+        if metadata.len() > MAX_LOG_FILE_SIZE {
+            let _ = std::fs::rename(&path, path.with_extension("log.old"));
+        }
+    }
+
+    OpenOptions::new().create(true).append(true).open(&path).ok()
+}
+
+/// 同时写入 stderr 和日志文件
+struct TeeWriter {
+    file: Option<File>,
+}
+
+impl Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let _ = std::io::stderr().write_all(buf);
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let _ = std::io::stderr().flush();
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.flush();
+        }
+        Ok(())
+    }
+}
 
 /// 初始化统一的日志配置
 /// 根据环境和配置自动设置日志级别
@@ -19,8 +73,9 @@ pub fn init_logger() {
         .and_then(|level| level.parse().ok())
         .unwrap_or(default_level);
     
+    // 内部过滤器放开到 Trace，实际级别由 log::set_max_level 控制，便于运行时调整
     builder
-        .filter_level(log_level)
+        .filter_level(LevelFilter::Trace)
         .format_timestamp_secs()
         .format_module_path(false)
         .format_target(false);
@@ -29,11 +84,11 @@ pub fn init_logger() {
     if !cfg!(debug_assertions) {
         builder
             .format(|buf, record| {
-                use std::io::Write;
                 // 生产环境下简化日志格式
                 writeln!(
                     buf,
-                    "[{}] {}",
+                    "[{}] [{}] {}",
+                    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
                     record.level(),
                     record.args()
                 )
@@ -42,10 +97,10 @@ pub fn init_logger() {
         // 开发环境下详细的日志格式
         builder
             .format(|buf, record| {
-                use std::io::Write;
                 writeln!(
                     buf,
-                    "[{}] [{}:{}] {}",
+                    "[{}] [{}] [{}:{}] {}",
+                    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
                     record.level(),
                     record.file().unwrap_or("unknown"),
                     record.line().unwrap_or(0),
@@ -53,8 +108,14 @@ pub fn init_logger() {
                 )
             });
     }
+
+    // 同时输出到日志文件，便于在应用内查看
+    builder.target(env_logger::Target::Pipe(Box::new(TeeWriter {
+        file: open_log_file(),
+    })));
     
     builder.init();
+    log::set_max_level(log_level);
     
     // 记录初始化信息
     log::info!("Logger initialized with level: {:?}", log_level);
@@ -65,6 +126,22 @@ pub fn init_logger() {
     }
 }
 
+/// 解析日志级别字符串，不区分大小写
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    level.trim().parse().ok()
+}
+
+/// 运行时调整日志级别
+pub fn set_runtime_level(level: LevelFilter) {
+    log::set_max_level(level);
+    log::info!("Log level changed to: {:?}", level);
+}
+
+/// 获取当前生效的日志级别
+pub fn current_level() -> LevelFilter {
+    log::max_level()
+}
+
 /// 日志宏的便捷封装，在生产环境下自动过滤调试日志
 #[macro_export]
 macro_rules! debug_log {
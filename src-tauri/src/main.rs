@@ -9,55 +9,79 @@ mod process;
 
 use checkpoint::state::CheckpointState;
 use commands::agents::{
-    cleanup_finished_processes, create_agent, delete_agent, delete_native_agents, execute_agent, export_agent,
+    cleanup_finished_processes, create_agent, delete_agent, delete_native_agents, execute_agent, execute_agent_blocking, get_agent_template_variables, resolve_effective_model, export_agent, export_agent_bundle,
     export_agent_to_file, fetch_github_agent_content, fetch_github_agents, get_agent,
-    get_agent_run, get_agent_run_with_real_time_metrics, get_claude_binary_path, refresh_claude_binary_path,
-    get_live_session_output, get_session_output, get_session_status, import_agent,
-    import_agent_from_file, import_agent_from_github, import_native_agents, init_database, kill_agent_session,
-    list_agent_runs, list_agent_runs_with_metrics, list_agents, list_claude_installations,
-    list_native_agents, list_running_sessions, load_agent_session_history, set_claude_binary_path, stream_session_output, update_agent, AgentDb,
-    get_environment_variables, save_environment_variables, get_enabled_environment_variables,
+    get_agent_run, get_agent_run_with_real_time_metrics, list_runs_without_session, recover_session_id, get_claude_binary_path, refresh_claude_binary_path, debug_spawn_environment,
+    get_live_session_output, get_all_live_outputs, get_session_output, export_run_as_messages, get_run_stderr, get_session_status, import_agent,
+    import_agent_from_file, import_agent_from_github, import_native_agents, init_database, kill_agent_session, cancel_pending_agent_runs,
+    set_agent_execution_paused, get_agent_execution_paused, AgentExecutionPauseState,
+    list_agent_runs, list_agent_runs_with_metrics, get_agent_usage_summary, RunMetricsCache, list_agents, list_claude_installations, add_custom_claude_installation,
+    list_custom_claude_installations, remove_custom_claude_installation, activate_claude_installation,
+    list_native_agents, list_running_sessions, load_agent_session_history, set_claude_binary_path, stream_session_output, update_agent, rename_agent, merge_agents, list_agent_runs_by_project, find_orphaned_agent_runs, repair_orphaned_agent_runs, get_binary_version_history, AgentDb,
+    get_environment_variables, save_environment_variables, get_enabled_environment_variables, upsert_environment_variable,
+    repair_env_var_index, check_database_integrity,
     get_environment_variable_groups, create_environment_variable_group, update_environment_variable_group, delete_environment_variable_group,
-    toggle_environment_variable_group_exclusive, get_available_models,
+    reorder_environment_variable_groups, clone_environment_variable_group,
+    toggle_environment_variable_group_exclusive, preview_group_enable_effect, get_available_models, list_agents_with_legacy_models, migrate_legacy_agent_models, test_claude_endpoint,
+    find_conflicting_model_definitions,
+    normalize_agent_models,
+    cancel_github_agent_fetch, GitHubFetchState,
+    take_native_agents_auto_import_result, NativeAgentAutoImport, NativeAgentAutoImportState,
 };
 use commands::claude::{
-    cancel_claude_execution, check_auto_checkpoint, check_claude_version, cleanup_old_checkpoints,
-    clear_checkpoint_manager, continue_claude_code, create_checkpoint, delete_project, delete_session, execute_claude_code,
+    cancel_claude_execution, check_auto_checkpoint, check_claude_version, check_claude_auth, detect_shell, get_claude_settings_capabilities, cleanup_old_checkpoints,
+    clear_checkpoint_manager, clear_checkpoint_manager_for_session, continue_claude_code, create_checkpoint, delete_project, delete_session, execute_claude_code,
     find_claude_md_files, fork_from_checkpoint, get_checkpoint_diff, get_checkpoint_settings,
     get_checkpoint_state_stats, get_claude_session_output, get_claude_settings, get_project_sessions,
-    get_recently_modified_files, get_session_timeline, get_system_prompt, list_checkpoints,
-    list_directory_contents, list_projects, list_running_claude_sessions, load_session_history,
-    save_session_history, open_new_session, read_claude_md_file, restore_checkpoint, resume_claude_code,
+    get_project_sessions_with_meta, get_project_additional_directories,
+    list_stale_project_dirs, prune_project_dir, get_large_sessions, truncate_session_jsonl,
+    get_recently_modified_files, estimate_project_context_tokens, get_session_timeline, export_timeline_dot, get_system_prompt, list_checkpoints, list_all_checkpoints,
+    list_directory_contents, list_projects, get_project_session_counts, list_historical_models, list_running_claude_sessions, load_session_history, get_session_messages, get_session_hash, verify_session_hash, list_session_tool_calls,
+    save_session_history, open_new_session, normalize_project_path, read_claude_md_file, restore_checkpoint, resume_claude_code, resume_latest_session,
     save_claude_md_file, delete_claude_md_file, save_claude_settings, update_claude_settings_with_env_group, update_claude_settings_with_model, save_system_prompt, search_files,
     track_checkpoint_message, track_session_messages, update_checkpoint_settings,
-    get_hooks_config, update_hooks_config, validate_hook_command,
-    ClaudeProcessState,
+    get_hooks_config, update_hooks_config, validate_hook_command, check_hook_dependencies, list_all_hooks, analyze_hooks,
+    ClaudeProcessState, SessionMetaCache, ProjectSessionCountCache, HistoricalModelCache,
 };
 use commands::mcp::{
     mcp_add, mcp_add_from_claude_desktop, mcp_add_json, mcp_get, mcp_get_server_status, mcp_list,
-    mcp_read_project_config, mcp_remove, mcp_remove_from_scope, mcp_reset_project_choices, mcp_save_project_config,
-    mcp_serve, mcp_test_connection, mcp_toggle_disabled, mcp_get_scope_priority,
-    mcp_read_claude_global_config, mcp_write_claude_global_config, mcp_backup_claude_global_config,
-    mcp_debug_claude_info,
+    mcp_read_project_config, mcp_remove, mcp_remove_from_scope, mcp_move_server, mcp_reset_project_choices, mcp_save_project_config,
+    mcp_serve, mcp_test_connection, mcp_toggle_disabled, mcp_set_servers_disabled, mcp_get_scope_priority,
+    mcp_read_claude_global_config, mcp_write_claude_global_config, mcp_backup_claude_global_config, mcp_list_claude_config_backups,
+    mcp_restore_claude_global_config, mcp_debug_claude_info, export_mcp_debug_info, mcp_validate_project_config, mcp_probe_stdio, mcp_audit_servers,
+    mcp_start_log_capture, mcp_stop_log_capture, MCPLogCaptureState,
 };
 use commands::settings_monitor::{
     check_configuration_consistency, start_settings_monitor, mark_internal_settings_update, refresh_configuration,
     trigger_configuration_check, get_detailed_configuration_status, check_config_consistency_simple, 
-    refresh_configuration_keep_model, SettingsMonitor,
+    refresh_configuration_keep_model, diff_settings_vs_db, get_active_settings_source, detect_settings_edit_origin, configure_settings_monitor, SettingsMonitor,
 };
 
 use commands::usage::{
-    get_session_stats, get_usage_by_date_range, get_usage_details, get_usage_stats,
+    estimate_run_cost, get_session_stats, get_usage_by_date_range, get_usage_details,
+    get_usage_stats, stream_usage_stats,
 };
 use commands::storage::{
     storage_list_tables, storage_read_table, storage_update_row, storage_delete_row,
-    storage_insert_row, storage_execute_sql, storage_reset_database,
-    get_app_setting, save_app_setting,
+    storage_insert_row, storage_execute_sql, storage_prepare_dangerous_sql,
+    storage_reset_database, get_app_storage_report, DangerousSqlState,
+    get_app_setting, save_app_setting, export_app_config, import_app_config,
+    create_settings_snapshot, list_settings_snapshots, restore_settings_snapshot,
+    delete_settings_snapshot,
 };
+use commands::activity::get_activity_feed;
+use commands::diagnostics::generate_diagnostic_bundle;
+use commands::agent_schedule::{
+    create_scheduled_agent_run, delete_scheduled_agent_run, list_scheduled_agent_runs,
+    update_scheduled_agent_run,
+};
+use commands::agent_sse::{start_agent_sse_server, stop_agent_sse_server, AgentSseServerState};
+use commands::agent_watch::{start_agent_watch, stop_agent_watch, AgentWatchState};
+use commands::logs::{get_log_level, get_recent_logs, set_log_level};
 use commands::proxy::{get_proxy_settings, save_proxy_settings, apply_proxy_settings, test_proxy_connection};
 use process::ProcessRegistryState;
 use std::sync::Mutex;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize unified logger
@@ -119,6 +143,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 // Apply the proxy settings
                 apply_proxy_settings(&proxy_settings);
+
+                // Restore the persisted log level (RUST_LOG still takes precedence)
+                if std::env::var("RUST_LOG").is_err() {
+                    if let Ok(conn) = db.0.lock() {
+                        if let Ok(level) = conn.query_row(
+                            "SELECT value FROM app_settings WHERE key = ?1",
+                            rusqlite::params![commands::logs::LOG_LEVEL_SETTING_KEY],
+                            |row| row.get::<_, String>(0),
+                        ) {
+                            if let Some(filter) = logger::parse_level(&level) {
+                                logger::set_runtime_level(filter);
+                            }
+                        }
+                    }
+                }
             }
 
             // Re-open the connection for the app to manage
@@ -128,6 +167,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             })?;
             app.manage(AgentDb(Mutex::new(conn)));
 
+            // Auto-import native agents from ~/.claude/agents if enabled
+            {
+                let auto_import = app
+                    .state::<AgentDb>()
+                    .0
+                    .lock()
+                    .ok()
+                    .and_then(|conn| {
+                        conn.query_row(
+                            "SELECT value FROM app_settings WHERE key = 'auto_import_native_agents'",
+                            [],
+                            |row| row.get::<_, String>(0),
+                        )
+                        .ok()
+                    })
+                    .map(|value| value == "true")
+                    .unwrap_or(false);
+
+                app.manage(NativeAgentAutoImportState::default());
+                if auto_import {
+                    let app_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        let db = app_handle.state::<AgentDb>();
+                        let outcome = match import_native_agents(db, None).await {
+                            Ok(result) => {
+                                log::info!("Auto-imported {} native agents on startup", result.imported_count);
+                                NativeAgentAutoImport {
+                                    imported_count: result.imported_count,
+                                    failed_files: result.failed_files,
+                                    error: None,
+                                }
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to auto-import native agents on startup: {}", e);
+                                NativeAgentAutoImport {
+                                    imported_count: 0,
+                                    failed_files: Vec::new(),
+                                    error: Some(e),
+                                }
+                            }
+                        };
+                        // Stored for take_native_agents_auto_import_result; the event only
+                        // reaches windows that were already listening
+                        if let Ok(mut slot) = app_handle.state::<NativeAgentAutoImportState>().0.lock() {
+                            *slot = Some(outcome.clone());
+                        }
+                        let _ = app_handle.emit("native-agents-auto-imported", outcome);
+                    });
+                }
+            }
+
+            // Warn about agents still on legacy model defaults
+            tauri::async_runtime::spawn(commands::agents::warn_legacy_agent_models(app.handle().clone()));
+
             // Initialize checkpoint state
             let checkpoint_state = CheckpointState::new();
 
@@ -147,27 +240,77 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 });
             }
 
+            // Periodically drop checkpoint managers for sessions that have gone idle
+            let sweep_state = checkpoint_state.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(checkpoint::state::MANAGER_EVICTION_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let evicted = sweep_state
+                        .evict_idle(checkpoint::state::MANAGER_IDLE_TIMEOUT)
+                        .await;
+                    if !evicted.is_empty() {
+                        log::info!("Evicted {} idle checkpoint managers", evicted.len());
+                    }
+                }
+            });
+
             app.manage(checkpoint_state);
 
             // Initialize process registry
-            app.manage(ProcessRegistryState::default());
+            let registry_state = ProcessRegistryState::default();
+            if let Ok(app_data_dir) = app.path().app_data_dir() {
+                registry_state.0.set_run_log_dir(app_data_dir.join("run_logs"));
+            }
+            app.manage(registry_state);
+            commands::agents::apply_max_live_output_setting(&app.handle());
 
             // Initialize Claude process state
             app.manage(ClaudeProcessState::default());
+            app.manage(SessionMetaCache::default());
+            app.manage(ProjectSessionCountCache::default());
+            app.manage(HistoricalModelCache::default());
+            app.manage(RunMetricsCache::default());
 
             // Initialize settings monitor
             app.manage(SettingsMonitor::new());
 
+            // Initialize GitHub agent fetch tracking
+            app.manage(GitHubFetchState::default());
+            app.manage(DangerousSqlState::default());
+            app.manage(AgentWatchState::default());
+            app.manage(AgentExecutionPauseState::default());
+            commands::agents::restore_agent_execution_state(&app.handle());
+            app.manage(AgentSseServerState::default());
+            app.manage(MCPLogCaptureState::default());
+
+            // Trigger recurring agent runs
+            commands::agent_schedule::start_agent_scheduler(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // Claude & Project Management
             list_projects,
+            get_project_session_counts,
+            list_historical_models,
+            get_activity_feed,
+            generate_diagnostic_bundle,
             get_project_sessions,
+            get_project_sessions_with_meta,
+            get_project_additional_directories,
+            list_stale_project_dirs,
+            prune_project_dir,
+            get_large_sessions,
+            truncate_session_jsonl,
             get_claude_settings,
             open_new_session,
+            normalize_project_path,
             get_system_prompt,
             check_claude_version,
+            check_claude_auth,
+            detect_shell,
+            get_claude_settings_capabilities,
             save_system_prompt,
             save_claude_settings,
             update_claude_settings_with_env_group,
@@ -177,27 +320,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             save_claude_md_file,
             delete_claude_md_file,
             load_session_history,
+            get_session_messages,
+            get_session_hash,
+            list_session_tool_calls,
+            verify_session_hash,
             execute_claude_code,
             continue_claude_code,
             resume_claude_code,
+            resume_latest_session,
             cancel_claude_execution,
             list_running_claude_sessions,
             get_claude_session_output,
             list_directory_contents,
             search_files,
             get_recently_modified_files,
+            estimate_project_context_tokens,
             delete_project,
             delete_session,
             get_hooks_config,
             update_hooks_config,
             validate_hook_command,
+            check_hook_dependencies,
+            list_all_hooks,
+            analyze_hooks,
 
             // Checkpoint Management
             create_checkpoint,
             restore_checkpoint,
             list_checkpoints,
+            list_all_checkpoints,
             fork_from_checkpoint,
             get_session_timeline,
+            export_timeline_dot,
             update_checkpoint_settings,
             get_checkpoint_diff,
             track_checkpoint_message,
@@ -206,58 +360,106 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             cleanup_old_checkpoints,
             get_checkpoint_settings,
             clear_checkpoint_manager,
+            clear_checkpoint_manager_for_session,
             get_checkpoint_state_stats,
 
             // Agent Management
             list_agents,
             list_native_agents,
             import_native_agents,
+            take_native_agents_auto_import_result,
             create_agent,
             update_agent,
+            rename_agent,
+            merge_agents,
+            list_agent_runs_by_project,
+            find_orphaned_agent_runs,
+            repair_orphaned_agent_runs,
+            get_binary_version_history,
             delete_agent,
             delete_native_agents,
             get_agent,
+            normalize_agent_models,
             execute_agent,
+            execute_agent_blocking,
+            get_agent_template_variables,
+            resolve_effective_model,
             list_agent_runs,
             get_agent_run,
             list_agent_runs_with_metrics,
+            get_agent_usage_summary,
             get_agent_run_with_real_time_metrics,
+            list_runs_without_session,
+            recover_session_id,
             list_running_sessions,
             kill_agent_session,
+            cancel_pending_agent_runs,
+            set_agent_execution_paused,
+            get_agent_execution_paused,
+            start_agent_watch,
+            stop_agent_watch,
+            create_scheduled_agent_run,
+            list_scheduled_agent_runs,
+            update_scheduled_agent_run,
+            delete_scheduled_agent_run,
+            start_agent_sse_server,
+            stop_agent_sse_server,
             get_session_status,
             cleanup_finished_processes,
             get_session_output,
+            get_run_stderr,
+            export_run_as_messages,
             get_live_session_output,
+            get_all_live_outputs,
             stream_session_output,
             load_agent_session_history,
             get_claude_binary_path,
             set_claude_binary_path,
             refresh_claude_binary_path,
+            debug_spawn_environment,
             list_claude_installations,
+            add_custom_claude_installation,
+            list_custom_claude_installations,
+            remove_custom_claude_installation,
+            activate_claude_installation,
             export_agent,
+            export_agent_bundle,
             export_agent_to_file,
             import_agent,
             import_agent_from_file,
             fetch_github_agents,
             fetch_github_agent_content,
             import_agent_from_github,
+            cancel_github_agent_fetch,
 
             // Environment Variables
             get_environment_variables,
             save_environment_variables,
+            upsert_environment_variable,
+            repair_env_var_index,
+            check_database_integrity,
             get_enabled_environment_variables,
             get_environment_variable_groups,
             create_environment_variable_group,
             update_environment_variable_group,
+            reorder_environment_variable_groups,
+            clone_environment_variable_group,
             delete_environment_variable_group,
             toggle_environment_variable_group_exclusive,
+            preview_group_enable_effect,
             get_available_models,
+            list_agents_with_legacy_models,
+            migrate_legacy_agent_models,
+            find_conflicting_model_definitions,
+            test_claude_endpoint,
 
             // Usage & Analytics
             get_usage_stats,
             get_usage_by_date_range,
             get_usage_details,
             get_session_stats,
+            stream_usage_stats,
+            estimate_run_cost,
 
             // MCP (Model Context Protocol)
             mcp_add,
@@ -265,7 +467,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             mcp_get,
             mcp_remove,
             mcp_remove_from_scope,
+            mcp_move_server,
             mcp_toggle_disabled,
+            mcp_set_servers_disabled,
             mcp_add_json,
             mcp_add_from_claude_desktop,
             mcp_serve,
@@ -274,10 +478,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             mcp_get_server_status,
             mcp_read_project_config,
             mcp_save_project_config,
+            mcp_validate_project_config,
             mcp_read_claude_global_config,
             mcp_write_claude_global_config,
             mcp_backup_claude_global_config,
+            mcp_list_claude_config_backups,
+            mcp_restore_claude_global_config,
             mcp_debug_claude_info,
+            export_mcp_debug_info,
+            mcp_probe_stdio,
+            mcp_audit_servers,
+            mcp_start_log_capture,
+            mcp_stop_log_capture,
             mcp_get_scope_priority,
 
             // Storage Management
@@ -287,15 +499,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             storage_delete_row,
             storage_insert_row,
             storage_execute_sql,
+            storage_prepare_dangerous_sql,
             storage_reset_database,
+            get_app_storage_report,
             get_app_setting,
             save_app_setting,
+            export_app_config,
+            import_app_config,
+            create_settings_snapshot,
+            list_settings_snapshots,
+            restore_settings_snapshot,
+            delete_settings_snapshot,
 
             // Slash Commands
             commands::slash_commands::slash_commands_list,
             commands::slash_commands::slash_command_get,
             commands::slash_commands::slash_command_save,
             commands::slash_commands::slash_command_delete,
+            commands::slash_commands::slash_command_preview,
+            commands::slash_commands::run_slash_command,
 
             // Proxy Settings
             get_proxy_settings,
@@ -305,13 +527,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Configuration Monitoring
             check_configuration_consistency,
             start_settings_monitor,
+            configure_settings_monitor,
             mark_internal_settings_update,
             refresh_configuration,
             trigger_configuration_check,
             get_detailed_configuration_status,
             check_config_consistency_simple,
             refresh_configuration_keep_model,
+            diff_settings_vs_db,
+            get_active_settings_source,
+            detect_settings_edit_origin,
             save_session_history,
+
+            // Logs
+            get_recent_logs,
+            set_log_level,
+            get_log_level,
         ])
         .run(tauri::generate_context!())
         .map_err(|e| {
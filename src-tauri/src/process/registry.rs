@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::process::Child;
 
+/// Default cap on in-memory live output per run (10 MB)
+pub const DEFAULT_MAX_LIVE_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
+
 /// Type of process being tracked
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessType {
@@ -26,6 +32,12 @@ pub struct ProcessInfo {
     pub project_path: String,
     pub task: String,
     pub model: String,
+    /// Resolved Claude binary used to spawn the process
+    #[serde(default)]
+    pub binary_path: Option<String>,
+    /// Version reported by the binary, if known
+    #[serde(default)]
+    pub binary_version: Option<String>,
 }
 
 /// Information about a running process with handle
@@ -34,19 +46,129 @@ pub struct ProcessHandle {
     pub info: ProcessInfo,
     pub child: Arc<Mutex<Option<Child>>>,
     pub live_output: Arc<Mutex<String>>,
+    /// Bytes dropped from the front of `live_output` to stay under the limit
+    pub dropped_output_bytes: Arc<AtomicU64>,
+    /// Full, untruncated output of agent runs on disk; flushed when the process is unregistered
+    pub output_log: Arc<Mutex<Option<std::io::BufWriter<std::fs::File>>>>,
+}
+
+/// Output broadcast to live subscribers such as the SSE server
+#[derive(Debug, Clone)]
+pub enum RunOutputEvent {
+    Line { run_id: i64, line: String },
+    Finished { run_id: i64, status: String },
 }
 
 /// Registry for tracking active agent processes
 pub struct ProcessRegistry {
     processes: Arc<Mutex<HashMap<i64, ProcessHandle>>>, // run_id -> ProcessHandle
     next_id: Arc<Mutex<i64>>, // Auto-incrementing ID for non-agent processes
+    output_events: tokio::sync::broadcast::Sender<RunOutputEvent>,
+    max_live_output_bytes: AtomicUsize,
+    run_log_dir: Mutex<Option<PathBuf>>,
+    opened_run_logs: Mutex<HashSet<i64>>, // Runs whose log was already started in this app session
 }
 
 impl ProcessRegistry {
     pub fn new() -> Self {
+        let (output_events, _) = tokio::sync::broadcast::channel(1024);
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             next_id: Arc::new(Mutex::new(1000000)), // Start at high number to avoid conflicts
+            output_events,
+            max_live_output_bytes: AtomicUsize::new(DEFAULT_MAX_LIVE_OUTPUT_BYTES),
+            run_log_dir: Mutex::new(None),
+            opened_run_logs: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Set how much live output each run keeps in memory; older output is dropped first
+    pub fn set_max_live_output_bytes(&self, bytes: usize) {
+        self.max_live_output_bytes.store(bytes.max(1024), Ordering::Relaxed);
+    }
+
+    /// Directory where the full output of agent runs is written
+    pub fn set_run_log_dir(&self, dir: PathBuf) {
+        if let Ok(mut run_log_dir) = self.run_log_dir.lock() {
+            *run_log_dir = Some(dir);
+        }
+    }
+
+    /// Path of an agent run's full output log, if run logs are enabled
+    pub fn run_log_path(&self, run_id: i64) -> Option<PathBuf> {
+        let dir = self.run_log_dir.lock().ok()?.clone()?;
+        Some(dir.join(format!("{}.jsonl", run_id)))
+    }
+
+    /// Path of an agent run's retained stderr, kept next to the run logs
+    pub fn stderr_log_path(&self, run_id: i64) -> Option<PathBuf> {
+        let dir = self.run_log_dir.lock().ok()?.clone()?;
+        Some(dir.join("stderr").join(format!("{}.log", run_id)))
+    }
+
+    /// Append a line of the Claude binary's stderr to the run's stderr log
+    pub fn append_stderr(&self, run_id: i64, line: &str) {
+        use std::io::Write;
+
+        let path = match self.stderr_log_path(run_id) {
+            Some(path) => path,
+            None => return,
+        };
+        if let Some(parent) = path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "{}", line.trim_end_matches(['\r', '\n'])));
+        if let Err(e) = result {
+            log::warn!("Failed to write stderr log {:?}: {}", path, e);
+        }
+    }
+
+    /// Delete the output and stderr logs of a run that was removed from the database
+    pub fn remove_run_logs(&self, run_id: i64) {
+        if let Ok(mut opened) = self.opened_run_logs.lock() {
+            opened.remove(&run_id);
+        }
+        for path in [self.run_log_path(run_id), self.stderr_log_path(run_id)]
+            .into_iter()
+            .flatten()
+        {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Failed to delete run log {:?}: {}", path, e),
+            }
+        }
+    }
+
+    /// Create the on-disk log for an agent run. A run registered again (re-spawned with a
+    /// fallback model) appends, so the output of the earlier attempt is kept.
+    fn open_run_log(&self, run_id: i64) -> Option<std::io::BufWriter<std::fs::File>> {
+        let path = self.run_log_path(run_id)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok()?;
+        }
+        let respawn = self
+            .opened_run_logs
+            .lock()
+            .map(|mut opened| !opened.insert(run_id))
+            .unwrap_or(false);
+        let file = if respawn {
+            std::fs::OpenOptions::new().create(true).append(true).open(&path)
+        } else {
+            std::fs::File::create(&path)
+        };
+        match file {
+            Ok(file) => Some(std::io::BufWriter::new(file)),
+            Err(e) => {
+                log::warn!("Failed to create run log {:?}: {}", path, e);
+                None
+            }
         }
     }
 
@@ -78,6 +200,8 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            binary_path: None,
+            binary_version: None,
         };
 
         self.register_process_internal(run_id, process_info, child)
@@ -102,15 +226,20 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            binary_path: None,
+            binary_version: None,
         };
 
         // For sidecar processes, we register without the child handle since it's managed differently
+        let output_log = self.open_run_log(run_id);
         let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
         
         let process_handle = ProcessHandle {
             info: process_info,
             child: Arc::new(Mutex::new(None)), // No tokio::process::Child handle for sidecar
             live_output: Arc::new(Mutex::new(String::new())),
+            dropped_output_bytes: Arc::new(AtomicU64::new(0)),
+            output_log: Arc::new(Mutex::new(output_log)),
         };
 
         processes.insert(run_id, process_handle);
@@ -125,6 +254,8 @@ impl ProcessRegistry {
         project_path: String,
         task: String,
         model: String,
+        binary_path: Option<String>,
+        binary_version: Option<String>,
     ) -> Result<i64, String> {
         let run_id = self.generate_id()?;
         
@@ -136,6 +267,8 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            binary_path,
+            binary_version,
         };
 
         // Register without child - Claude sessions use ClaudeProcessState for process management
@@ -145,6 +278,8 @@ impl ProcessRegistry {
             info: process_info,
             child: Arc::new(Mutex::new(None)), // No child handle for Claude sessions
             live_output: Arc::new(Mutex::new(String::new())),
+            dropped_output_bytes: Arc::new(AtomicU64::new(0)),
+            output_log: Arc::new(Mutex::new(None)),
         };
 
         processes.insert(run_id, process_handle);
@@ -158,12 +293,15 @@ impl ProcessRegistry {
         process_info: ProcessInfo,
         child: Child,
     ) -> Result<(), String> {
+        let output_log = self.open_run_log(run_id);
         let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
 
         let process_handle = ProcessHandle {
             info: process_info,
             child: Arc::new(Mutex::new(Some(child))),
             live_output: Arc::new(Mutex::new(String::new())),
+            dropped_output_bytes: Arc::new(AtomicU64::new(0)),
+            output_log: Arc::new(Mutex::new(output_log)),
         };
 
         processes.insert(run_id, process_handle);
@@ -201,8 +339,20 @@ impl ProcessRegistry {
     /// Unregister a process (called when it completes)
     #[allow(dead_code)]
     pub fn unregister_process(&self, run_id: i64) -> Result<(), String> {
-        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
-        processes.remove(&run_id);
+        let handle = {
+            let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+            processes.remove(&run_id)
+        };
+        // Flush the buffered run log outside the registry lock
+        if let Some(handle) = handle {
+            if let Ok(mut output_log) = handle.output_log.lock() {
+                if let Some(mut writer) = output_log.take() {
+                    if let Err(e) = writer.flush() {
+                        log::warn!("Failed to flush run log for run {}: {}", run_id, e);
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -520,28 +670,134 @@ impl ProcessRegistry {
         }
     }
 
-    /// Append to live output for a process
+    /// Append to live output for a process.
+    /// The in-memory buffer keeps only the newest output once it exceeds the limit;
+    /// agent runs also get every line written to their on-disk run log.
     pub fn append_live_output(&self, run_id: i64, output: &str) -> Result<(), String> {
+        let output_log = {
+            let processes = self.processes.lock().map_err(|e| e.to_string())?;
+            processes.get(&run_id).map(|handle| handle.output_log.clone())
+        };
+        // Write the run log without holding the registry lock
+        if let Some(output_log) = output_log {
+            if let Ok(mut output_log) = output_log.lock() {
+                if let Some(writer) = output_log.as_mut() {
+                    if let Err(e) = writeln!(writer, "{}", output) {
+                        log::warn!("Failed to write run log for run {}: {}", run_id, e);
+                        *output_log = None;
+                    }
+                }
+            }
+        }
+
         let processes = self.processes.lock().map_err(|e| e.to_string())?;
         if let Some(handle) = processes.get(&run_id) {
             let mut live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
             live_output.push_str(output);
             live_output.push('\n');
+
+            let max_bytes = self.max_live_output_bytes.load(Ordering::Relaxed);
+            if live_output.len() > max_bytes {
+                // Trim to 90% of the limit at a line boundary so trimming isn't needed on every line
+                let mut target = live_output.len() - max_bytes * 9 / 10;
+                while !live_output.is_char_boundary(target) {
+                    target += 1;
+                }
+                let cut = live_output[target..]
+                    .find('\n')
+                    .map(|i| target + i + 1)
+                    .unwrap_or(live_output.len());
+                live_output.drain(..cut);
+                handle.dropped_output_bytes.fetch_add(cut as u64, Ordering::Relaxed);
+            }
+
+            let _ = self.output_events.send(RunOutputEvent::Line {
+                run_id,
+                line: output.to_string(),
+            });
         }
         Ok(())
     }
 
+    /// Snapshot the live output of a run and subscribe to everything appended after it.
+    /// Both happen under the registry lock so no line is missed or duplicated.
+    pub fn subscribe_output(
+        &self,
+        run_id: i64,
+    ) -> Result<(String, tokio::sync::broadcast::Receiver<RunOutputEvent>), String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let receiver = self.output_events.subscribe();
+        let snapshot = match processes.get(&run_id) {
+            Some(handle) => Self::live_output_with_notice(run_id, handle)?,
+            None => String::new(),
+        };
+        Ok((snapshot, receiver))
+    }
+
+    /// Live output, prefixed with a notice line when older output was dropped
+    fn live_output_with_notice(run_id: i64, handle: &ProcessHandle) -> Result<String, String> {
+        let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
+        let dropped = handle.dropped_output_bytes.load(Ordering::Relaxed);
+        if dropped == 0 {
+            return Ok(live_output.clone());
+        }
+
+        let notice = serde_json::json!({
+            "type": "system",
+            "subtype": "output_truncated",
+            "run_id": run_id,
+            "dropped_bytes": dropped,
+            "message": format!(
+                "Earlier output ({} bytes) was dropped from memory; the full output is in the run log",
+                dropped
+            ),
+        });
+        Ok(format!("{}\n{}", notice, live_output))
+    }
+
+    /// Subscribe to output and completion events of every run
+    pub fn subscribe_run_events(&self) -> tokio::sync::broadcast::Receiver<RunOutputEvent> {
+        self.output_events.subscribe()
+    }
+
+    /// Tell output subscribers that a run has ended
+    pub fn notify_run_finished(&self, run_id: i64, status: &str) {
+        let _ = self.output_events.send(RunOutputEvent::Finished {
+            run_id,
+            status: status.to_string(),
+        });
+    }
+
     /// Get live output for a process
     pub fn get_live_output(&self, run_id: i64) -> Result<String, String> {
         let processes = self.processes.lock().map_err(|e| e.to_string())?;
         if let Some(handle) = processes.get(&run_id) {
-            let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
-            Ok(live_output.clone())
+            Self::live_output_with_notice(run_id, handle)
         } else {
             Ok(String::new())
         }
     }
 
+    /// Get the last `tail_lines` lines of live output for every registered process,
+    /// skipping processes whose buffer is empty
+    pub fn get_all_live_outputs(&self, tail_lines: usize) -> Result<HashMap<i64, String>, String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let mut outputs = HashMap::new();
+
+        for (run_id, handle) in processes.iter() {
+            let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
+            if live_output.is_empty() {
+                continue;
+            }
+
+            let lines: Vec<&str> = live_output.lines().collect();
+            let start = lines.len().saturating_sub(tail_lines);
+            outputs.insert(*run_id, lines[start..].join("\n"));
+        }
+
+        Ok(outputs)
+    }
+
     /// Cleanup finished processes
     #[allow(dead_code)]
     pub async fn cleanup_finished_processes(&self) -> Result<Vec<i64>, String> {
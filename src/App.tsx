@@ -1,7 +1,8 @@
 import { useState, useEffect, useCallback } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { Plus, Loader2, Bot, FolderCode } from "lucide-react";
-import { api, type Project, type Session, type ClaudeMdFile } from "@/lib/api";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { api, type Agent, type Project, type Session, type ClaudeMdFile, type NativeAgentAutoImport } from "@/lib/api";
 import { OutputCacheProvider } from "@/lib/outputCache";
 import { useI18n } from "@/lib/i18n";
 import { TabProvider } from "@/contexts/TabContext";
@@ -109,6 +110,58 @@ function AppContent() {
     logger.debug("Notification manager initialized");
   }, []);
 
+  // Report the startup native agent auto-import, which may finish before or after mount
+  useEffect(() => {
+    const report = (result: NativeAgentAutoImport | null) => {
+      if (!result) return;
+      if (result.error) {
+        setToast({ message: `Failed to auto-import native agents: ${result.error}`, type: "error" });
+      } else if (result.imported_count > 0 || result.failed_files.length > 0) {
+        const failed = result.failed_files.length > 0 ? `, ${result.failed_files.length} failed` : "";
+        setToast({ message: `Auto-imported ${result.imported_count} native agents${failed}`, type: "info" });
+      }
+    };
+    const check = () => {
+      api.takeNativeAgentsAutoImportResult().then(report).catch(() => {});
+    };
+
+    let unlisten: UnlistenFn | undefined;
+    let disposed = false;
+    listen("native-agents-auto-imported", check).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+    check();
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // Warn about agents on legacy models. The startup event can fire before this mounts,
+  // so the list is also pulled once directly.
+  useEffect(() => {
+    const report = (agents: Agent[]) => {
+      if (agents.length === 0) return;
+      setToast({
+        message: `${agents.length} agent(s) use unavailable models: ${agents.map((a) => `${a.name} (${a.model})`).join(", ")}`,
+        type: "info",
+      });
+    };
+
+    let unlisten: UnlistenFn | undefined;
+    let disposed = false;
+    listen<Agent[]>("legacy-agent-models-detected", (event) => report(event.payload)).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+    api.listAgentsWithLegacyModels().then(report).catch(() => {});
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
   // Show config dialog when refresh is needed
   useEffect(() => {
     if (status && status.needs_refresh) {
@@ -224,9 +224,12 @@ export const AgentsModal: React.FC<AgentsModalProps> = ({ open, onOpenChange })
 
   const handleImportNativeAgents = async () => {
     try {
-      const count = await api.importNativeAgents();
+      const { imported_count: count, failed_files: failedFiles } = await api.importNativeAgents();
 
-      if (count === 0) {
+      if (failedFiles.length > 0) {
+        const names = failedFiles.map((f) => f.file_name).join(", ");
+        setToast({ message: `导入了 ${count} 个原生智能体，${failedFiles.length} 个文件解析失败: ${names}`, type: "error" });
+      } else if (count === 0) {
         setToast({ message: "未找到要导入的原生智能体", type: "error" });
       } else {
         setToast({ message: `成功导入了 ${count} 个原生智能体`, type: "success" });
@@ -10,6 +10,7 @@ import {
   DialogHeader,
   DialogTitle,
 } from './ui/dialog';
+import type { RefreshResult } from '@/lib/api';
 
 interface ConfigStatus {
   needs_refresh: boolean;
@@ -56,18 +57,22 @@ export const ConfigConflictDialog: React.FC<RefreshDialogProps> = ({
     try {
       // 获取当前选择的模型
       const currentModel = localStorage.getItem('selected-model');
+      let result: RefreshResult;
       if (currentModel) {
         // 使用新的刷新函数，保持当前模型选择
-        await invoke('refresh_configuration_keep_model', { 
+        result = await invoke<RefreshResult>('refresh_configuration_keep_model', { 
           groupId: null,
           currentSelectedModel: currentModel
         });
       } else {
         // 后备方案：使用原来的刷新
-        await invoke('refresh_configuration', { 
+        result = await invoke<RefreshResult>('refresh_configuration', { 
           groupId: null
         });
       }
+      if (!result.verified) {
+        console.warn('Settings on disk differ from written values:', result.mismatched_keys);
+      }
       
       // 重置详情状态
       setShowDetails(false);
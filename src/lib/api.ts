@@ -143,9 +143,22 @@ export interface AgentExport {
     default_task?: string;
     model: string;
     hooks?: string;
+    allowed_tools?: string;
+    disallowed_tools?: string;
+    on_complete_command?: string;
+    on_complete_webhook?: string;
   };
 }
 
+export interface NativeAgentImportResult {
+  imported_count: number;
+  failed_files: { file_name: string; error: string }[];
+}
+
+export interface NativeAgentAutoImport extends NativeAgentImportResult {
+  error: string | null;
+}
+
 export interface GitHubAgentFile {
   name: string;
   path: string;
@@ -336,6 +349,28 @@ export interface EnvironmentVariable {
   updated_at?: string;
 }
 
+/**
+ * One environment variable written to settings.json by a refresh
+ */
+export interface WrittenEnvVar {
+  key: string;
+  /** Secrets (tokens, keys, passwords) are masked */
+  value: string;
+}
+
+/**
+ * Outcome of applying the enabled environment groups to settings.json
+ */
+export interface RefreshResult {
+  settings_path: string;
+  written: WrittenEnvVar[];
+  /** The file re-read from disk contains every written value */
+  verified: boolean;
+  /** Keys whose value on disk differs from what was written */
+  mismatched_keys: string[];
+  message: string;
+}
+
 /**
  * Represents model information from the backend
  */
@@ -659,11 +694,11 @@ export const api = {
    * Updates Claude settings.json with environment variables from active group
    * @param groupId - The ID of the active environment group
    * @param projectPath - Optional project path for project-level settings
-   * @returns Promise resolving when the settings are updated
+   * @returns Promise resolving to the written variables and their verification result
    */
-  async updateClaudeSettingsWithEnvGroup(groupId: number | null, projectPath?: string): Promise<string> {
+  async updateClaudeSettingsWithEnvGroup(groupId: number | null, projectPath?: string): Promise<RefreshResult> {
     try {
-      return await invoke<string>("update_claude_settings_with_env_group", { groupId, projectPath });
+      return await invoke<RefreshResult>("update_claude_settings_with_env_group", { groupId, projectPath });
     } catch (error) {
       logger.error("Failed to update Claude settings with environment group:", error);
       throw error;
@@ -779,17 +814,58 @@ export const api = {
 
   /**
    * Import native agents from .claude/agents directory to database
-   * @returns Promise resolving to the number of agents imported
+   * @param atomic - When true, any file that fails to parse rolls back the whole import
+   * @returns Promise resolving to the import count and the files that failed
    */
-  async importNativeAgents(): Promise<number> {
+  async importNativeAgents(atomic?: boolean): Promise<NativeAgentImportResult> {
     try {
-      return await invoke<number>("import_native_agents");
+      return await invoke<NativeAgentImportResult>("import_native_agents", { atomic });
     } catch (error) {
       logger.error("Failed to import native agents:", error);
       throw error;
     }
   },
 
+  /**
+   * Takes the outcome of the startup auto-import of native agents
+   * @returns Promise resolving to the outcome, or null if none ran or it was already taken
+   */
+  async takeNativeAgentsAutoImportResult(): Promise<NativeAgentAutoImport | null> {
+    try {
+      return await invoke<NativeAgentAutoImport | null>("take_native_agents_auto_import_result");
+    } catch (error) {
+      logger.error("Failed to get native agents auto-import result:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists agents whose model isn't among the available models. The same list is sent
+   * in the `legacy-agent-models-detected` event at startup.
+   * @returns Promise resolving to the affected agents
+   */
+  async listAgentsWithLegacyModels(): Promise<Agent[]> {
+    try {
+      return await invoke<Agent[]>("list_agents_with_legacy_models");
+    } catch (error) {
+      logger.error("Failed to list agents with legacy models:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Rewrites legacy agent models to the configured default model
+   * @returns Promise resolving to the number of agents updated
+   */
+  async migrateLegacyAgentModels(): Promise<number> {
+    try {
+      return await invoke<number>("migrate_legacy_agent_models");
+    } catch (error) {
+      logger.error("Failed to migrate legacy agent models:", error);
+      throw error;
+    }
+  },
+
   /**
    * Creates a new agent
    * @param name - The agent name
@@ -812,13 +888,15 @@ export const api = {
   ): Promise<Agent> {
     try {
       return await invoke<Agent>("create_agent", {
-        name,
-        icon,
-        systemPrompt: system_prompt,
-        defaultTask: default_task,
-        model,
-        hooks,
-        source: source || 'claudia',
+        input: {
+          name,
+          icon,
+          system_prompt,
+          default_task,
+          model,
+          hooks,
+          source: source || 'claudia',
+        },
       });
     } catch (error) {
       logger.error("Failed to create agent:", error);
@@ -849,12 +927,14 @@ export const api = {
     try {
       return await invoke<Agent>("update_agent", {
         id,
-        name,
-        icon,
-        systemPrompt: system_prompt,
-        defaultTask: default_task,
-        model,
-        hooks,
+        input: {
+          name,
+          icon,
+          system_prompt,
+          default_task,
+          model,
+          hooks,
+        },
       });
     } catch (error) {
       logger.error("Failed to update agent:", error);
@@ -1615,11 +1695,18 @@ export const api = {
   },
 
   /**
-   * Toggles the disabled status of an MCP server
+   * Toggles the disabled status of an MCP server. Project servers are recorded in the
+   * CLI's disabledMcpjsonServers; user/local servers are moved out of ~/.claude.json
+   * while disabled and restored when enabled.
    */
-  async mcpToggleDisabled(name: string, disabled: boolean, projectPath?: string): Promise<string> {
+  async mcpToggleDisabled(
+    name: string,
+    disabled: boolean,
+    projectPath?: string,
+    scope?: "user" | "local" | "project"
+  ): Promise<string> {
     try {
-      return await invoke<string>("mcp_toggle_disabled", { name, disabled, projectPath });
+      return await invoke<string>("mcp_toggle_disabled", { name, disabled, projectPath, scope });
     } catch (error) {
       logger.error("Failed to toggle MCP server disabled status:", error);
       throw error;